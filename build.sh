@@ -1,35 +1,15 @@
 RUSTFLAGS="-C link-arg=-zstack-size=32768" cargo build --release --target wasm32-unknown-unknown
 cd ./target/wasm32-unknown-unknown/release
-ontio-wasm-build oep5_receiver.wasm
 ontio-wasm-build bridge.wasm
 ontio-wasm-build ontd_wrapper.wasm
-mv oep5_receiver_optimized.wasm oep5_receiver_optimized1.wasm
 cd ../../../
 
-sed -i -e 's/unsupported action/unsupported action2/' ./contracts/oep5-receiver/src/lib.rs
-rm ./contracts/oep5-receiver/src/lib.rs-e
-RUSTFLAGS="-C link-arg=-zstack-size=32768" cargo build --release --target wasm32-unknown-unknown
-cd ./target/wasm32-unknown-unknown/release
-ontio-wasm-build oep5_receiver.wasm
-mv oep5_receiver_optimized.wasm oep5_receiver_optimized2.wasm
-cd ../../../
-
-
-sed -i -e 's/unsupported action2/unsupported action3/' ./contracts/oep5-receiver/src/lib.rs
-rm ./contracts/oep5-receiver/src/lib.rs-e
-RUSTFLAGS="-C link-arg=-zstack-size=32768" cargo build --release --target wasm32-unknown-unknown
-cd ./target/wasm32-unknown-unknown/release
-ontio-wasm-build oep5_receiver.wasm
-mv oep5_receiver_optimized.wasm oep5_receiver_optimized3.wasm
-cd ../../../
-
-sed -i -e 's/unsupported action3/unsupported action4/' ./contracts/oep5-receiver/src/lib.rs
-rm ./contracts/oep5-receiver/src/lib.rs-e
-RUSTFLAGS="-C link-arg=-zstack-size=32768" cargo build --release --target wasm32-unknown-unknown
-cd ./target/wasm32-unknown-unknown/release
-ontio-wasm-build oep5_receiver.wasm
-mv oep5_receiver_optimized.wasm oep5_receiver_optimized4.wasm
-cd ../../../
-
-sed -i -e 's/unsupported action4/unsupported action/' ./contracts/oep5-receiver/src/lib.rs
-rm ./contracts/oep5-receiver/src/lib.rs-e
\ No newline at end of file
+# every receiver is built with its own instance id, so each one gets a distinct code hash
+RECEIVER_COUNT=${RECEIVER_COUNT:-4}
+for i in $(seq 1 $RECEIVER_COUNT); do
+    RECEIVER_INSTANCE_ID=$i RUSTFLAGS="-C link-arg=-zstack-size=32768" cargo build --release --target wasm32-unknown-unknown -p oep5-receiver
+    cd ./target/wasm32-unknown-unknown/release
+    ontio-wasm-build oep5_receiver.wasm
+    mv oep5_receiver_optimized.wasm oep5_receiver_optimized$i.wasm
+    cd ../../../
+done
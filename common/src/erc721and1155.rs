@@ -1,38 +1,143 @@
-use ontio_std::abi::Source;
+use crate::ethabi::{decode, decode_revert, encode_call, ParamType, Revert, Token};
+use crate::selector::selector;
+use core::fmt;
 use ontio_std::contract::eth;
 use ontio_std::prelude::*;
 use ontio_std::types::U256;
 
-const MINT_ID_WONT: [u8; 4] = [0x40, 0xc1, 0x0f, 0x19];
-pub fn mint_wont(caller: &Address, target: &Address, to: &Address, amount: U128) {
-    eth::evm_invoke(caller, target, gen_wont_mint_data(to, amount).as_slice());
+//why an evm call did not return what the caller expects
+#[derive(Clone, Debug, PartialEq)]
+pub enum CallError {
+    //nothing returned where a value is expected, e.g. the target is not a contract
+    EmptyReturn,
+    //an erc20 style function returned false instead of reverting
+    ReturnedFalse,
+    Reverted(Revert),
+    //the return data does not match the expected types
+    InvalidReturn,
 }
 
-fn gen_wont_mint_data(to_acct: &Address, amount: U128) -> Vec<u8> {
-    [
-        MINT_ID_WONT.as_ref(),
-        format_addr(to_acct).as_ref(),
-        format_amount(amount).as_ref(),
-    ]
-    .concat()
+impl fmt::Display for CallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CallError::EmptyReturn => write!(f, "empty return data"),
+            CallError::ReturnedFalse => write!(f, "returned false"),
+            CallError::Reverted(revert) => write!(f, "{}", revert),
+            CallError::InvalidReturn => write!(f, "invalid return data"),
+        }
+    }
 }
 
-pub fn balance_of_erc721(caller: &Address, target: &Address, user: &Address) -> U128 {
-    let res = eth::evm_invoke(caller, target, gen_erc721_balance_of_data(user).as_slice());
+//result of a state changing call declared to return returns, nothing returned is a success
+//as well. only a declared bool is checked, other return values are not a status
+pub fn check_void_result(returns: &[ParamType], res: &[u8]) -> Result<(), CallError> {
     if res.is_empty() {
-        return U128::new(0);
+        return Ok(());
+    }
+    if let Some(revert) = decode_revert(res) {
+        return Err(CallError::Reverted(revert));
+    }
+    if returns.is_empty() {
+        return Ok(());
     }
-    let mut source = Source::new(res.as_slice());
-    let h = source.read_h256().unwrap();
-    let res = U256::from_big_endian(h.as_bytes());
-    res.as_u128()
+    let tokens = decode(returns, res).map_err(|_| CallError::InvalidReturn)?;
+    if res.len() != returns.len() * 32 {
+        return Err(CallError::InvalidReturn);
+    }
+    if returns == [ParamType::Bool] && tokens[0] == Token::Bool(false) {
+        return Err(CallError::ReturnedFalse);
+    }
+    Ok(())
+}
+
+//result of a call returning static types, a revert never has the length of whole words
+pub fn check_result(types: &[ParamType], res: &[u8]) -> Result<Vec<Token>, CallError> {
+    if res.is_empty() {
+        return Err(CallError::EmptyReturn);
+    }
+    if let Some(revert) = decode_revert(res) {
+        return Err(CallError::Reverted(revert));
+    }
+    decode(types, res).map_err(|_| CallError::InvalidReturn)
+}
+
+fn call_void(caller: &Address, target: &Address, data: &[u8], method: &str, returns: &[ParamType]) {
+    let res = eth::evm_invoke(caller, target, data);
+    if let Err(err) = check_void_result(returns, res.as_slice()) {
+        panic!("{} failed, {}", method, err);
+    }
+}
+
+fn call_uint(caller: &Address, target: &Address, data: &[u8], method: &str) -> U256 {
+    let res = eth::evm_invoke(caller, target, data);
+    match check_result(&[ParamType::Uint], res.as_slice()) {
+        Ok(mut tokens) => tokens.remove(0).into_uint().unwrap(),
+        Err(err) => panic!("{} failed, {}", method, err),
+    }
+}
+
+const MINT_ID_WONT: [u8; 4] = selector("mint(address,uint256)");
+pub fn mint_wont(caller: &Address, target: &Address, to: &Address, amount: U256) {
+    mint_erc20(caller, target, to, amount);
+}
+
+pub fn mint_erc20(caller: &Address, target: &Address, to: &Address, amount: U256) {
+    call_void(
+        caller,
+        target,
+        gen_wont_mint_data(to, amount).as_slice(),
+        "mint",
+        &[ParamType::Bool],
+    );
+}
+
+pub fn burn_erc20(caller: &Address, target: &Address, from: &Address, amount: U256) {
+    call_void(
+        caller,
+        target,
+        gen_erc20_burn_data(from, amount).as_slice(),
+        "burnFrom",
+        &[],
+    );
 }
 
-pub fn mint_erc721(caller: &Address, target: &Address, to: &Address, token_id: U128) {
-    eth::evm_invoke(
+//erc20 and erc721 share the same balanceOf(address) selector
+pub fn balance_of_erc20(caller: &Address, target: &Address, user: &Address) -> U256 {
+    balance_of_erc721(caller, target, user)
+}
+
+const BURN_FROM_ID_ERC20: [u8; 4] = selector("burnFrom(address,uint256)");
+
+fn gen_erc20_burn_data(from_acct: &Address, amount: U256) -> Vec<u8> {
+    encode_call(
+        BURN_FROM_ID_ERC20,
+        &[Token::Address(*from_acct), Token::Uint(amount)],
+    )
+}
+
+fn gen_wont_mint_data(to_acct: &Address, amount: U256) -> Vec<u8> {
+    encode_call(
+        MINT_ID_WONT,
+        &[Token::Address(*to_acct), Token::Uint(amount)],
+    )
+}
+
+pub fn balance_of_erc721(caller: &Address, target: &Address, user: &Address) -> U256 {
+    call_uint(
+        caller,
+        target,
+        gen_erc721_balance_of_data(user).as_slice(),
+        "balanceOf",
+    )
+}
+
+pub fn mint_erc721(caller: &Address, target: &Address, to: &Address, token_id: U256) {
+    call_void(
         caller,
         target,
         gen_erc721_mint_data(to, token_id).as_slice(),
+        "mint",
+        &[ParamType::Uint],
     );
 }
 
@@ -40,44 +145,170 @@ pub fn mint_erc1155(
     caller: &Address,
     target: &Address,
     to: &Address,
-    token_id: U128,
-    amount: U128,
+    token_id: U256,
+    amount: U256,
 ) {
-    eth::evm_invoke(
+    call_void(
         caller,
         target,
         gen_erc1155_mint_data(to, token_id, amount).as_slice(),
+        "mint",
+        &[],
     );
 }
 
-// const TRANSFER_ID: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
-// const TRANSFER_FROM_ID: [u8; 4] = [0x23, 0xb8, 0x72, 0xdd];
-const MINT_ID_ERC721: [u8; 4] = [0x40, 0xc1, 0x0f, 0x19];
-const BALANCEOF_ID_ERC721: [u8; 4] = [0x70, 0xa0, 0x82, 0x31];
-const BALANCEOF_ID_ERC1155: [u8; 4] = [0x00, 0xfd, 0xd5, 0x8e];
-const MINT_ID_ERC1155: [u8; 4] = [0x15, 0x6e, 0x29, 0xf6];
+pub fn owner_of_erc721(caller: &Address, target: &Address, token_id: U256) -> Address {
+    let res = eth::evm_invoke(
+        caller,
+        target,
+        gen_erc721_owner_of_data(token_id).as_slice(),
+    );
+    match check_result(&[ParamType::Address], res.as_slice()) {
+        Ok(mut tokens) => tokens.remove(0).into_address().unwrap(),
+        Err(err) => panic!("ownerOf failed, {}", err),
+    }
+}
 
-fn gen_erc721_mint_data(to_acct: &Address, token_id: U128) -> Vec<u8> {
-    [
-        MINT_ID_ERC721.as_ref(),
-        format_addr(to_acct).as_ref(),
-        format_amount(token_id).as_ref(),
-    ]
-    .concat()
+pub fn burn_erc721(caller: &Address, target: &Address, token_id: U256) {
+    call_void(
+        caller,
+        target,
+        gen_erc721_burn_data(token_id).as_slice(),
+        "burn",
+        &[],
+    );
 }
 
-fn gen_erc1155_mint_data(to_acct: &Address, token_id: U128, amount: U128) -> Vec<u8> {
-    [
-        MINT_ID_ERC1155.as_ref(),
-        format_addr(to_acct).as_ref(),
-        format_amount(token_id).as_ref(),
-        format_amount(amount).as_ref(),
-    ]
-    .concat()
+pub fn mint_batch_erc1155(
+    caller: &Address,
+    target: &Address,
+    to: &Address,
+    token_ids: &[U256],
+    amounts: &[U256],
+) {
+    call_void(
+        caller,
+        target,
+        gen_erc1155_mint_batch_data(to, token_ids, amounts).as_slice(),
+        "mintBatch",
+        &[],
+    );
+}
+
+pub fn burn_erc1155(
+    caller: &Address,
+    target: &Address,
+    from: &Address,
+    token_id: U256,
+    amount: U256,
+) {
+    call_void(
+        caller,
+        target,
+        gen_erc1155_burn_data(from, token_id, amount).as_slice(),
+        "burn",
+        &[],
+    );
+}
+
+const SUPPORTS_INTERFACE_ID: [u8; 4] = selector("supportsInterface(bytes4)");
+const INTERFACE_ID_ERC721: [u8; 4] = [0x80, 0xac, 0x58, 0xcd];
+const INTERFACE_ID_ERC1155: [u8; 4] = [0xd9, 0xb6, 0x7a, 0x26];
+const INTERFACE_ID_INVALID: [u8; 4] = [0xff; 4];
+
+pub fn supports_erc721(caller: &Address, target: &Address) -> bool {
+    supports_interface(caller, target, INTERFACE_ID_ERC721)
+}
+
+pub fn supports_erc1155(caller: &Address, target: &Address) -> bool {
+    supports_interface(caller, target, INTERFACE_ID_ERC1155)
+}
+
+//erc165 detection, the contract must also deny 0xffffffff
+fn supports_interface(caller: &Address, target: &Address, interface_id: [u8; 4]) -> bool {
+    query_interface(caller, target, INTERFACE_ID_INVALID) == Some(false)
+        && query_interface(caller, target, interface_id) == Some(true)
+}
+
+fn query_interface(caller: &Address, target: &Address, interface_id: [u8; 4]) -> Option<bool> {
+    let data = encode_call(
+        SUPPORTS_INTERFACE_ID,
+        &[Token::FixedBytes(interface_id.to_vec())],
+    );
+    let res = eth::evm_invoke(caller, target, data.as_slice());
+    check_result(&[ParamType::Bool], res.as_slice())
+        .ok()
+        .and_then(|mut tokens| tokens.remove(0).into_bool())
+}
+
+// const TRANSFER_ID: [u8; 4] = selector("transfer(address,uint256)");
+// const TRANSFER_FROM_ID: [u8; 4] = selector("transferFrom(address,address,uint256)");
+const MINT_ID_ERC721: [u8; 4] = selector("mint(address,uint256)");
+const BALANCEOF_ID_ERC721: [u8; 4] = selector("balanceOf(address)");
+const OWNEROF_ID_ERC721: [u8; 4] = selector("ownerOf(uint256)");
+const BURN_ID_ERC721: [u8; 4] = selector("burn(uint256)");
+const BALANCEOF_ID_ERC1155: [u8; 4] = selector("balanceOf(address,uint256)");
+const MINT_ID_ERC1155: [u8; 4] = selector("mint(address,uint256,uint256)");
+const BURN_ID_ERC1155: [u8; 4] = selector("burn(address,uint256,uint256)");
+const MINT_BATCH_ID_ERC1155: [u8; 4] = selector("mintBatch(address,uint256[],uint256[],bytes)");
+
+fn gen_erc721_mint_data(to_acct: &Address, token_id: U256) -> Vec<u8> {
+    encode_call(
+        MINT_ID_ERC721,
+        &[Token::Address(*to_acct), Token::Uint(token_id)],
+    )
+}
+
+fn gen_erc1155_mint_data(to_acct: &Address, token_id: U256, amount: U256) -> Vec<u8> {
+    encode_call(
+        MINT_ID_ERC1155,
+        &[
+            Token::Address(*to_acct),
+            Token::Uint(token_id),
+            Token::Uint(amount),
+        ],
+    )
+}
+
+//mintBatch(address,uint256[],uint256[],bytes), the data argument is always empty
+fn gen_erc1155_mint_batch_data(to_acct: &Address, token_ids: &[U256], amounts: &[U256]) -> Vec<u8> {
+    assert_eq!(
+        token_ids.len(),
+        amounts.len(),
+        "ids and amounts length mismatch"
+    );
+    encode_call(
+        MINT_BATCH_ID_ERC1155,
+        &[
+            Token::Address(*to_acct),
+            Token::Array(token_ids.iter().map(|&id| Token::Uint(id)).collect()),
+            Token::Array(amounts.iter().map(|&amt| Token::Uint(amt)).collect()),
+            Token::Bytes(Vec::new()),
+        ],
+    )
+}
+
+fn gen_erc1155_burn_data(from_acct: &Address, token_id: U256, amount: U256) -> Vec<u8> {
+    encode_call(
+        BURN_ID_ERC1155,
+        &[
+            Token::Address(*from_acct),
+            Token::Uint(token_id),
+            Token::Uint(amount),
+        ],
+    )
 }
 
 fn gen_erc721_balance_of_data(addr: &Address) -> Vec<u8> {
-    [BALANCEOF_ID_ERC721.as_ref(), format_addr(addr).as_ref()].concat()
+    encode_call(BALANCEOF_ID_ERC721, &[Token::Address(*addr)])
+}
+
+fn gen_erc721_owner_of_data(token_id: U256) -> Vec<u8> {
+    encode_call(OWNEROF_ID_ERC721, &[Token::Uint(token_id)])
+}
+
+fn gen_erc721_burn_data(token_id: U256) -> Vec<u8> {
+    encode_call(BURN_ID_ERC721, &[Token::Uint(token_id)])
 }
 
 pub fn format_addr(addr: &Address) -> [u8; 32] {
@@ -90,35 +321,27 @@ pub fn format_amount(amt: U128) -> [u8; 32] {
     U256::from(amt).to_be_bytes()
 }
 
-// pub const SAFE_TRANSFER_FROM_ID: [u8; 4] = [0xf2, 0x42, 0x43, 0x2a];
+// pub const SAFE_TRANSFER_FROM_ID: [u8; 4] = selector("safeTransferFrom(address,address,uint256,uint256,bytes)");
 
 pub fn balance_of_erc1155(
     caller: &Address,
     target: &Address,
     user: &Address,
-    token_id: U128,
-) -> U128 {
-    let res = eth::evm_invoke(
+    token_id: U256,
+) -> U256 {
+    call_uint(
         caller,
         target,
         gen_erc1155_balance_of_data(user, token_id).as_slice(),
-    );
-    if res.is_empty() {
-        return U128::new(0);
-    }
-    let mut source = Source::new(res.as_slice());
-    let h = source.read_h256().unwrap();
-    let res = U256::from_big_endian(h.as_bytes());
-    res.as_u128()
+        "balanceOf",
+    )
 }
 
-fn gen_erc1155_balance_of_data(user: &Address, token_id: U128) -> Vec<u8> {
-    [
-        BALANCEOF_ID_ERC1155.as_ref(),
-        format_addr(user).as_ref(),
-        format_amount(token_id).as_ref(),
-    ]
-    .concat()
+fn gen_erc1155_balance_of_data(user: &Address, token_id: U256) -> Vec<u8> {
+    encode_call(
+        BALANCEOF_ID_ERC1155,
+        &[Token::Address(*user), Token::Uint(token_id)],
+    )
 }
 
 #[test]
@@ -126,3 +349,91 @@ fn test() {
     let addr = &Address::repeat_byte(1);
     println!("{:?}", format_addr(&addr).as_ref());
 }
+
+#[test]
+fn test_gen_erc1155_mint_batch_data() {
+    let to = Address::repeat_byte(1);
+    let data = gen_erc1155_mint_batch_data(
+        &to,
+        &[U256::from(U128::new(1))],
+        &[U256::from(U128::new(2))],
+    );
+    let words: Vec<[u8; 32]> = [0x80, 0xc0, 0x100, 1, 1, 1, 2, 0]
+        .iter()
+        .map(|&n| format_amount(U128::new(n)))
+        .collect();
+    let expected = [
+        MINT_BATCH_ID_ERC1155.as_ref(),
+        format_addr(&to).as_ref(),
+        words.concat().as_slice(),
+    ]
+    .concat();
+    assert_eq!(data, expected);
+}
+
+#[test]
+fn test_selectors() {
+    assert_eq!(MINT_ID_WONT, [0x40, 0xc1, 0x0f, 0x19]);
+    assert_eq!(BURN_FROM_ID_ERC20, [0x79, 0xcc, 0x67, 0x90]);
+    assert_eq!(MINT_ID_ERC721, [0x40, 0xc1, 0x0f, 0x19]);
+    assert_eq!(BALANCEOF_ID_ERC721, [0x70, 0xa0, 0x82, 0x31]);
+    assert_eq!(OWNEROF_ID_ERC721, [0x63, 0x52, 0x21, 0x1e]);
+    assert_eq!(BURN_ID_ERC721, [0x42, 0x96, 0x6c, 0x68]);
+    assert_eq!(BALANCEOF_ID_ERC1155, [0x00, 0xfd, 0xd5, 0x8e]);
+    assert_eq!(MINT_ID_ERC1155, [0x15, 0x6e, 0x29, 0xf6]);
+    assert_eq!(BURN_ID_ERC1155, [0xf5, 0x29, 0x8a, 0xca]);
+    assert_eq!(MINT_BATCH_ID_ERC1155, [0x1f, 0x7f, 0xdf, 0xfa]);
+    assert_eq!(SUPPORTS_INTERFACE_ID, [0x01, 0xff, 0xc9, 0xa7]);
+    assert_eq!(
+        crate::selector!("transfer(address,uint256)"),
+        [0xa9, 0x05, 0x9c, 0xbb]
+    );
+    assert_eq!(
+        crate::selector!("transferFrom(address,address,uint256)"),
+        [0x23, 0xb8, 0x72, 0xdd]
+    );
+}
+
+#[test]
+fn test_check_result() {
+    let one = format_amount(U128::new(1));
+    let bool_ret = [ParamType::Bool];
+    assert_eq!(check_void_result(&bool_ret, &[]), Ok(()));
+    assert_eq!(check_void_result(&bool_ret, &one), Ok(()));
+    assert_eq!(
+        check_void_result(&bool_ret, &[0; 32]),
+        Err(CallError::ReturnedFalse)
+    );
+    //a mint returning the new token id, id 0 included, is not a status
+    let uint_ret = [ParamType::Uint];
+    assert_eq!(check_void_result(&uint_ret, &[0; 32]), Ok(()));
+    assert_eq!(check_void_result(&uint_ret, &[7; 32]), Ok(()));
+    assert_eq!(check_void_result(&[], &one), Ok(()));
+    assert_eq!(
+        check_void_result(&uint_ret, &[0; 20]),
+        Err(CallError::InvalidReturn)
+    );
+    let revert = crate::ethabi::encode_call(
+        selector("Error(string)"),
+        &[Token::String(String::from("caller is not minter"))],
+    );
+    assert_eq!(
+        check_void_result(&uint_ret, &revert),
+        Err(CallError::Reverted(Revert::Error(String::from(
+            "caller is not minter"
+        ))))
+    );
+    //an empty balanceOf result is a failed call, not a zero balance
+    assert_eq!(
+        check_result(&[ParamType::Uint], &[]),
+        Err(CallError::EmptyReturn)
+    );
+    assert_eq!(
+        check_result(&[ParamType::Uint], &[0; 32]),
+        Ok(vec![Token::Uint(U256::zero())])
+    );
+    assert_eq!(
+        check_result(&[ParamType::Uint], &[0; 20]),
+        Err(CallError::InvalidReturn)
+    );
+}
@@ -0,0 +1,433 @@
+use crate::selector::selector;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::{fmt, iter};
+use ontio_std::types::{Address, U128, U256};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParamType {
+    Address,
+    Uint,
+    Bool,
+    //bytes1 ~ bytes32
+    FixedBytes(usize),
+    Bytes,
+    String,
+    Array(Box<ParamType>),
+    FixedArray(Box<ParamType>, usize),
+    Tuple(Vec<ParamType>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    Address(Address),
+    Uint(U256),
+    Bool(bool),
+    FixedBytes(Vec<u8>),
+    Bytes(Vec<u8>),
+    String(String),
+    Array(Vec<Token>),
+    FixedArray(Vec<Token>),
+    Tuple(Vec<Token>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    //data is shorter than the types need
+    InvalidLength,
+    //padding or offset is not valid
+    InvalidData,
+    InvalidUtf8,
+}
+
+//the data a reverted call returns
+#[derive(Clone, Debug, PartialEq)]
+pub enum Revert {
+    //Error(string), from require and revert with a message
+    Error(String),
+    //Panic(uint256), from failed asserts, overflows and the like
+    Panic(U256),
+    //custom error, its selector and the encoded arguments
+    Custom([u8; 4], Vec<u8>),
+}
+
+const ERROR_SELECTOR: [u8; 4] = selector("Error(string)");
+const PANIC_SELECTOR: [u8; 4] = selector("Panic(uint256)");
+
+impl ParamType {
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            ParamType::Bytes | ParamType::String | ParamType::Array(_) => true,
+            ParamType::FixedArray(inner, _) => inner.is_dynamic(),
+            ParamType::Tuple(types) => types.iter().any(|t| t.is_dynamic()),
+            _ => false,
+        }
+    }
+
+    fn head_size(&self) -> usize {
+        match self {
+            _ if self.is_dynamic() => 32,
+            ParamType::FixedArray(inner, len) => inner.head_size() * len,
+            ParamType::Tuple(types) => types.iter().map(|t| t.head_size()).sum(),
+            _ => 32,
+        }
+    }
+}
+
+impl Token {
+    pub fn uint(value: U128) -> Token {
+        Token::Uint(U256::from(value))
+    }
+
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            Token::Bytes(_) | Token::String(_) | Token::Array(_) => true,
+            Token::FixedArray(tokens) | Token::Tuple(tokens) => {
+                tokens.iter().any(|t| t.is_dynamic())
+            }
+            _ => false,
+        }
+    }
+
+    fn head_size(&self) -> usize {
+        match self {
+            _ if self.is_dynamic() => 32,
+            Token::FixedArray(tokens) | Token::Tuple(tokens) => {
+                tokens.iter().map(|t| t.head_size()).sum()
+            }
+            _ => 32,
+        }
+    }
+
+    pub fn into_address(self) -> Option<Address> {
+        match self {
+            Token::Address(addr) => Some(addr),
+            _ => None,
+        }
+    }
+
+    pub fn into_uint(self) -> Option<U256> {
+        match self {
+            Token::Uint(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn into_bool(self) -> Option<bool> {
+        match self {
+            Token::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn into_string(self) -> Option<String> {
+        match self {
+            Token::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+//oep4, oep5 and oep8 contracts only support 128 bits numbers
+pub fn u256_to_u128(value: U256) -> Option<U128> {
+    let bytes = value.to_be_bytes();
+    if bytes[..16].iter().any(|&b| b != 0) {
+        return None;
+    }
+    let mut low = [0u8; 16];
+    low.copy_from_slice(&bytes[16..]);
+    Some(U128::new(u128::from_be_bytes(low)))
+}
+
+//big endian bytes at most 32 bytes long, the way token ids are passed to the contracts
+pub fn u256_from_be_slice(bytes: &[u8]) -> U256 {
+    assert!(bytes.len() <= 32, "uint256 is longer than 32 bytes");
+    let mut word = [0u8; 32];
+    word[32 - bytes.len()..].copy_from_slice(bytes);
+    U256::from_big_endian(&word)
+}
+
+//decode revert data, return None if it is not shaped like Error(string), Panic(uint256)
+//or a custom error (a selector followed by whole words)
+pub fn decode_revert(data: &[u8]) -> Option<Revert> {
+    if data.len() < 4 || (data.len() - 4) % 32 != 0 {
+        return None;
+    }
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(&data[..4]);
+    let args = &data[4..];
+    let decoded = match selector {
+        ERROR_SELECTOR => decode(&[ParamType::String], args)
+            .ok()
+            .and_then(|mut tokens| tokens.remove(0).into_string())
+            .map(Revert::Error),
+        PANIC_SELECTOR => decode(&[ParamType::Uint], args)
+            .ok()
+            .and_then(|mut tokens| tokens.remove(0).into_uint())
+            .map(Revert::Panic),
+        _ => None,
+    };
+    Some(decoded.unwrap_or_else(|| Revert::Custom(selector, args.to_vec())))
+}
+
+impl fmt::Display for Revert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Revert::Error(reason) => write!(f, "reverted: {}", reason),
+            Revert::Panic(code) => {
+                write!(f, "panicked: 0x")?;
+                write_hex(f, &code.to_be_bytes())
+            }
+            Revert::Custom(selector, args) => {
+                write!(f, "custom error 0x")?;
+                write_hex(f, selector)?;
+                if !args.is_empty() {
+                    write!(f, ", args 0x")?;
+                    write_hex(f, args)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn write_hex(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    bytes.iter().try_for_each(|b| write!(f, "{:02x}", b))
+}
+
+pub fn encode_call(selector: [u8; 4], tokens: &[Token]) -> Vec<u8> {
+    [selector.as_ref(), encode(tokens).as_slice()].concat()
+}
+
+//encode tokens as the arguments of a call, the same as a tuple without the outer offset
+pub fn encode(tokens: &[Token]) -> Vec<u8> {
+    let head_len: usize = tokens.iter().map(|t| t.head_size()).sum();
+    let mut head = Vec::with_capacity(head_len);
+    let mut tail = Vec::new();
+    for token in tokens.iter() {
+        if token.is_dynamic() {
+            head.extend_from_slice(&encode_usize(head_len + tail.len()));
+            tail.extend_from_slice(encode_token(token).as_slice());
+        } else {
+            head.extend_from_slice(encode_token(token).as_slice());
+        }
+    }
+    head.extend_from_slice(tail.as_slice());
+    head
+}
+
+fn encode_token(token: &Token) -> Vec<u8> {
+    match token {
+        Token::Address(addr) => {
+            let mut res = [0u8; 32];
+            res[12..].copy_from_slice(addr.as_bytes());
+            res.to_vec()
+        }
+        Token::Uint(value) => value.to_be_bytes().to_vec(),
+        Token::Bool(b) => encode_usize(*b as usize).to_vec(),
+        Token::FixedBytes(bytes) => {
+            assert!(bytes.len() <= 32, "fixed bytes is longer than 32");
+            pad_right(bytes)
+        }
+        Token::Bytes(bytes) => [
+            encode_usize(bytes.len()).as_ref(),
+            pad_right(bytes).as_slice(),
+        ]
+        .concat(),
+        Token::String(s) => {
+            let bytes = s.as_bytes();
+            [
+                encode_usize(bytes.len()).as_ref(),
+                pad_right(bytes).as_slice(),
+            ]
+            .concat()
+        }
+        Token::Array(tokens) => [
+            encode_usize(tokens.len()).as_ref(),
+            encode(tokens).as_slice(),
+        ]
+        .concat(),
+        Token::FixedArray(tokens) | Token::Tuple(tokens) => encode(tokens),
+    }
+}
+
+fn encode_usize(value: usize) -> [u8; 32] {
+    let mut res = [0u8; 32];
+    res[24..].copy_from_slice(&(value as u64).to_be_bytes());
+    res
+}
+
+fn pad_right(bytes: &[u8]) -> Vec<u8> {
+    let len = (bytes.len() + 31) / 32 * 32;
+    let mut res = bytes.to_vec();
+    res.resize(len, 0);
+    res
+}
+
+//decode return data or call arguments (without the selector) as the given types
+pub fn decode(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, Error> {
+    decode_tuple(types, data)
+}
+
+fn decode_tuple(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::with_capacity(types.len());
+    let mut pos = 0;
+    for ty in types.iter() {
+        if ty.is_dynamic() {
+            let offset = read_usize(data, pos)?;
+            let tail = data.get(offset..).ok_or(Error::InvalidLength)?;
+            tokens.push(decode_param(ty, tail)?);
+        } else {
+            let head = data.get(pos..).ok_or(Error::InvalidLength)?;
+            tokens.push(decode_param(ty, head)?);
+        }
+        pos += ty.head_size();
+    }
+    Ok(tokens)
+}
+
+fn decode_param(ty: &ParamType, data: &[u8]) -> Result<Token, Error> {
+    match ty {
+        ParamType::Address => {
+            let word = read_word(data, 0)?;
+            if word[..12].iter().any(|&b| b != 0) {
+                return Err(Error::InvalidData);
+            }
+            Ok(Token::Address(Address::from_slice(&word[12..])))
+        }
+        ParamType::Uint => Ok(Token::Uint(U256::from_big_endian(read_word(data, 0)?))),
+        ParamType::Bool => match read_usize(data, 0)? {
+            0 => Ok(Token::Bool(false)),
+            1 => Ok(Token::Bool(true)),
+            _ => Err(Error::InvalidData),
+        },
+        ParamType::FixedBytes(len) => {
+            let word = read_word(data, 0)?;
+            if *len > 32 || word[*len..].iter().any(|&b| b != 0) {
+                return Err(Error::InvalidData);
+            }
+            Ok(Token::FixedBytes(word[..*len].to_vec()))
+        }
+        ParamType::Bytes => Ok(Token::Bytes(read_bytes(data)?.to_vec())),
+        ParamType::String => {
+            let bytes = read_bytes(data)?.to_vec();
+            let s = String::from_utf8(bytes).map_err(|_| Error::InvalidUtf8)?;
+            Ok(Token::String(s))
+        }
+        ParamType::Array(inner) => {
+            let len = read_usize(data, 0)?;
+            let body = data.get(32..).ok_or(Error::InvalidLength)?;
+            //every element takes at least one word, reject lengths the data can not hold
+            if len > body.len() / 32 {
+                return Err(Error::InvalidLength);
+            }
+            let types: Vec<ParamType> = iter::repeat(inner.as_ref().clone()).take(len).collect();
+            Ok(Token::Array(decode_tuple(types.as_slice(), body)?))
+        }
+        ParamType::FixedArray(inner, len) => {
+            let types: Vec<ParamType> = iter::repeat(inner.as_ref().clone()).take(*len).collect();
+            Ok(Token::FixedArray(decode_tuple(types.as_slice(), data)?))
+        }
+        ParamType::Tuple(types) => Ok(Token::Tuple(decode_tuple(types.as_slice(), data)?)),
+    }
+}
+
+fn read_word(data: &[u8], pos: usize) -> Result<&[u8], Error> {
+    data.get(pos..pos + 32).ok_or(Error::InvalidLength)
+}
+
+fn read_usize(data: &[u8], pos: usize) -> Result<usize, Error> {
+    let word = read_word(data, pos)?;
+    if word[..24].iter().any(|&b| b != 0) {
+        return Err(Error::InvalidData);
+    }
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&word[24..]);
+    usize::try_from(u64::from_be_bytes(buf)).map_err(|_| Error::InvalidData)
+}
+
+fn read_bytes(data: &[u8]) -> Result<&[u8], Error> {
+    let len = read_usize(data, 0)?;
+    data.get(32..)
+        .and_then(|body| body.get(..len))
+        .ok_or(Error::InvalidLength)
+}
+
+#[test]
+fn test_encode_dynamic() {
+    let addr = Address::repeat_byte(1);
+    let tokens = [
+        Token::Address(addr),
+        Token::Array(vec![Token::uint(U128::new(1)), Token::uint(U128::new(2))]),
+        Token::Bytes(b"abc".to_vec()),
+    ];
+    let mut expected = Vec::new();
+    expected.extend_from_slice(&encode_token(&Token::Address(addr)));
+    expected.extend_from_slice(&encode_usize(3 * 32));
+    expected.extend_from_slice(&encode_usize(6 * 32));
+    expected.extend_from_slice(&encode_usize(2));
+    expected.extend_from_slice(&encode_usize(1));
+    expected.extend_from_slice(&encode_usize(2));
+    expected.extend_from_slice(&encode_usize(3));
+    let mut abc = [0u8; 32];
+    abc[..3].copy_from_slice(b"abc");
+    expected.extend_from_slice(&abc);
+    assert_eq!(encode(&tokens), expected);
+}
+
+#[test]
+fn test_u256_to_u128() {
+    let max = U256::from(U128::new(u128::MAX));
+    assert_eq!(u256_to_u128(max), Some(U128::new(u128::MAX)));
+    assert_eq!(u256_to_u128(u256_from_be_slice(&[1; 17])), None);
+    assert_eq!(u256_from_be_slice(&[1, 0]), U256::from(U128::new(256)));
+}
+
+#[test]
+fn test_decode_revert() {
+    let data = encode_call(ERROR_SELECTOR, &[Token::String(String::from("not owner"))]);
+    let revert = decode_revert(&data).unwrap();
+    assert_eq!(revert, Revert::Error(String::from("not owner")));
+    assert_eq!(alloc::format!("{}", revert), "reverted: not owner");
+    let data = encode_call(PANIC_SELECTOR, &[Token::uint(U128::new(0x11))]);
+    assert_eq!(
+        decode_revert(&data),
+        Some(Revert::Panic(U256::from(U128::new(0x11))))
+    );
+    let revert = decode_revert(&[0xde, 0xad, 0xbe, 0xef]).unwrap();
+    assert_eq!(alloc::format!("{}", revert), "custom error 0xdeadbeef");
+    //a single word is a return value, not a revert
+    assert_eq!(decode_revert(&[0; 32]), None);
+    assert_eq!(decode_revert(&[]), None);
+}
+
+#[test]
+fn test_decode_roundtrip() {
+    let types = [
+        ParamType::Bool,
+        ParamType::String,
+        ParamType::Tuple(vec![ParamType::Uint, ParamType::Bytes]),
+        ParamType::FixedArray(Box::new(ParamType::Address), 2),
+        ParamType::FixedBytes(4),
+    ];
+    let tokens = vec![
+        Token::Bool(true),
+        Token::String(String::from("ontology")),
+        Token::Tuple(vec![
+            Token::uint(U128::new(u128::MAX)),
+            Token::Bytes(vec![7; 40]),
+        ]),
+        Token::FixedArray(vec![
+            Token::Address(Address::repeat_byte(2)),
+            Token::Address(Address::repeat_byte(3)),
+        ]),
+        Token::FixedBytes(vec![0x80, 0xac, 0x58, 0xcd]),
+    ];
+    let data = encode(&tokens);
+    assert_eq!(decode(&types, &data), Ok(tokens));
+    assert_eq!(
+        decode(&types, &data[..data.len() - 64]),
+        Err(Error::InvalidLength)
+    );
+}
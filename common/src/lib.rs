@@ -1,7 +1,12 @@
 #![cfg_attr(not(feature = "mock"), no_std)]
 #![feature(proc_macro_hygiene)]
 
+extern crate alloc;
 extern crate ontio_std;
 
 pub mod erc721and1155;
+pub mod ethabi;
+#[cfg(feature = "mock")]
+pub mod mock;
 pub mod oep5and8;
+pub mod selector;
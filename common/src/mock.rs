@@ -0,0 +1,864 @@
+//in-memory fakes of the contracts the bridge talks to, routed from the mock runtime
+use crate::ethabi::{decode, encode, encode_call, u256_to_u128, ParamType, Token};
+use crate::selector::selector;
+use alloc::collections::BTreeMap;
+use core::cell::RefCell;
+use core::mem::{swap, take};
+use ontio_std::abi::{Encoder, Sink, Source};
+use ontio_std::mock::{build_runtime, RuntimeHandle};
+use ontio_std::prelude::*;
+use ontio_std::runtime::{address, contract_migrate};
+use ontio_std::types::{u128_from_neo_bytes, U256};
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+
+pub trait FakeContract {
+    //None makes the mocked call_contract fail, like calling a contract that aborts
+    fn call(&mut self, input: &[u8]) -> Option<Vec<u8>>;
+}
+
+type Storage = HashMap<Vec<u8>, Vec<u8>>;
+
+//the action table of a contract linked into the test, like the bridge's dispatch
+pub type Dispatch = fn(&[u8]) -> Vec<u8>;
+
+#[derive(Clone, Default)]
+pub struct World {
+    contracts: Rc<RefCell<BTreeMap<Address, Rc<RefCell<dyn FakeContract>>>>>,
+    wasm: Rc<RefCell<BTreeMap<Address, Dispatch>>>,
+    //storage of every wasm contract, except the one running at the moment
+    storages: Rc<RefCell<BTreeMap<Address, Storage>>>,
+    handle: Rc<RefCell<Weak<RuntimeHandle>>>,
+}
+
+impl World {
+    pub fn new() -> World {
+        World::default()
+    }
+
+    pub fn deploy<T: FakeContract + 'static>(&self, addr: &Address, fake: T) -> Rc<RefCell<T>> {
+        let fake = Rc::new(RefCell::new(fake));
+        self.contracts.borrow_mut().insert(*addr, fake.clone());
+        fake
+    }
+
+    //a real contract at addr, calls run its dispatch with its own address and storage
+    pub fn deploy_wasm(&self, addr: &Address, dispatch: Dispatch) {
+        self.wasm.borrow_mut().insert(*addr, dispatch);
+    }
+
+    pub fn call(&self, target: &Address, input: &[u8]) -> Option<Vec<u8>> {
+        let dispatch = self.wasm.borrow().get(target).cloned();
+        if let Some(dispatch) = dispatch {
+            return Some(self.enter(target, dispatch, input));
+        }
+        //release the registry before the call, fakes may call other fakes
+        let fake = self.contracts.borrow().get(target).cloned()?;
+        let mut fake = fake.borrow_mut();
+        fake.call(input)
+    }
+
+    //wasm::call_contract sends the encoded tuple, the same as the contract reads in invoke
+    pub fn call_wasm<T: Encoder>(&self, target: &Address, param: T) -> Option<Vec<u8>> {
+        let mut sink = Sink::new(16);
+        sink.write(param);
+        self.call(target, sink.bytes())
+    }
+
+    //route every contract call of a fresh mock runtime to the contracts in this world
+    pub fn install(&self) -> Rc<RuntimeHandle> {
+        let world = self.clone();
+        let handle = Rc::new(build_runtime());
+        handle.on_contract_call(move |target, input| world.call(target, input));
+        *self.handle.borrow_mut() = Rc::downgrade(&handle);
+        handle
+    }
+
+    //switches the runtime over to target, a panic unwinds through the guard and switches back
+    fn enter(&self, target: &Address, dispatch: Dispatch, input: &[u8]) -> Vec<u8> {
+        let handle = self
+            .handle
+            .borrow()
+            .upgrade()
+            .expect("world is not installed");
+        let caller = address();
+        assert!(caller != *target, "reentrant wasm call");
+        let mut storage = self
+            .storages
+            .borrow_mut()
+            .remove(target)
+            .unwrap_or_default();
+        swap(&mut *handle.storage_mut(), &mut storage);
+        handle.address(target);
+        let _guard = Leave {
+            world: self,
+            handle: &handle,
+            target: *target,
+            caller,
+            caller_storage: storage,
+        };
+        dispatch(input)
+    }
+}
+
+//puts the caller back in place when the called contract returns or aborts
+struct Leave<'a> {
+    world: &'a World,
+    handle: &'a RuntimeHandle,
+    target: Address,
+    caller: Address,
+    caller_storage: Storage,
+}
+
+impl Drop for Leave<'_> {
+    fn drop(&mut self) {
+        let mut storage = take(&mut self.caller_storage);
+        swap(&mut *self.handle.storage_mut(), &mut storage);
+        self.world
+            .storages
+            .borrow_mut()
+            .insert(self.target, storage);
+        self.handle.address(&self.caller);
+    }
+}
+
+//the address a contract moves to when migrated to code, asked from the mocked contract_migrate
+pub fn migrated_address(code: &[u8]) -> Address {
+    contract_migrate(code, 3, "", "", "", "", "")
+}
+
+fn ret<T: Encoder>(value: T) -> Option<Vec<u8>> {
+    let mut sink = Sink::new(16);
+    sink.write(value);
+    Some(sink.bytes().to_vec())
+}
+
+fn key(value: U256) -> [u8; 32] {
+    value.to_be_bytes()
+}
+
+fn amount(value: U256) -> u128 {
+    u256_to_u128(value).expect("amount is out of range").raw()
+}
+
+fn uint(value: u128) -> Token {
+    Token::uint(U128::new(value))
+}
+
+//wasm oep5, transfer moves the token from its current owner
+#[derive(Default)]
+pub struct FakeOep5 {
+    pub owners: BTreeMap<u128, Address>,
+}
+
+impl FakeOep5 {
+    pub fn mint(&mut self, to: &Address, token_id: u128) {
+        assert!(!self.owners.contains_key(&token_id), "token id exists");
+        self.owners.insert(token_id, *to);
+    }
+
+    pub fn owner_of(&self, token_id: u128) -> Address {
+        self.owners.get(&token_id).cloned().unwrap_or_default()
+    }
+
+    pub fn balance_of(&self, account: &Address) -> u128 {
+        self.owners
+            .values()
+            .filter(|&owner| owner == account)
+            .count() as u128
+    }
+}
+
+impl FakeContract for FakeOep5 {
+    fn call(&mut self, input: &[u8]) -> Option<Vec<u8>> {
+        let mut source = Source::new(input);
+        let action: &str = source.read().ok()?;
+        match action {
+            "balanceOf" => {
+                let account: &Address = source.read().ok()?;
+                ret(U128::new(self.balance_of(account)))
+            }
+            "ownerOf" => {
+                let token_id: U128 = source.read().ok()?;
+                ret(self.owner_of(token_id.raw()))
+            }
+            "transfer" => {
+                let (to, token_id): (&Address, U128) = source.read().ok()?;
+                match self.owners.get_mut(&token_id.raw()) {
+                    Some(owner) => {
+                        *owner = *to;
+                        ret(true)
+                    }
+                    None => ret(false),
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+//oep8 answering wasm calls, or neovm calls when is_neovm is set
+#[derive(Default)]
+pub struct FakeOep8 {
+    pub balances: BTreeMap<(Address, u128), u128>,
+    pub is_neovm: bool,
+}
+
+impl FakeOep8 {
+    pub fn mint(&mut self, to: &Address, token_id: u128, amount: u128) {
+        *self.balances.entry((*to, token_id)).or_insert(0) += amount;
+    }
+
+    pub fn balance_of(&self, account: &Address, token_id: u128) -> u128 {
+        self.balances
+            .get(&(*account, token_id))
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    fn transfer(&mut self, from: &Address, to: &Address, token_id: u128, amount: u128) -> bool {
+        let balance = self.balance_of(from, token_id);
+        if balance < amount {
+            return false;
+        }
+        self.balances.insert((*from, token_id), balance - amount);
+        self.mint(to, token_id, amount);
+        true
+    }
+
+    fn call_neovm(&mut self, input: &[u8]) -> Option<Vec<u8>> {
+        let (method, args) = read_neo_call(input)?;
+        let mut args = args.into_iter();
+        match method.as_str() {
+            "balanceOf" => {
+                let account = args.next()?.into_address()?;
+                let token_id = args.next()?.into_int()?;
+                neo_ret_int(self.balance_of(&account, token_id))
+            }
+            "transfer" => {
+                let from = args.next()?.into_address()?;
+                let to = args.next()?.into_address()?;
+                let token_id = args.next()?.into_int()?;
+                let amount = args.next()?.into_int()?;
+                neo_ret_bool(self.transfer(&from, &to, token_id, amount))
+            }
+            //every state is one [from, to, tokenId, amount] argument
+            "transferMulti" => {
+                let backup = self.balances.clone();
+                for state in args {
+                    let mut state = match state {
+                        NeoValue::List(items) if items.len() == 4 => items.into_iter(),
+                        _ => return None,
+                    };
+                    let from = state.next()?.into_address()?;
+                    let to = state.next()?.into_address()?;
+                    let token_id = state.next()?.into_int()?;
+                    let amount = state.next()?.into_int()?;
+                    if !self.transfer(&from, &to, token_id, amount) {
+                        self.balances = backup;
+                        return None;
+                    }
+                }
+                neo_ret_bool(true)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl FakeContract for FakeOep8 {
+    fn call(&mut self, input: &[u8]) -> Option<Vec<u8>> {
+        if self.is_neovm {
+            return self.call_neovm(input);
+        }
+        let mut source = Source::new(input);
+        let action: &str = source.read().ok()?;
+        match action {
+            "balanceOf" => {
+                let (account, token_id): (&Address, U128) = source.read().ok()?;
+                ret(U128::new(self.balance_of(account, token_id.raw())))
+            }
+            "transfer" => {
+                let (from, to, token_id, amount): (&Address, &Address, U128, U128) =
+                    source.read().ok()?;
+                ret(self.transfer(from, to, token_id.raw(), amount.raw()))
+            }
+            "transferMulti" => {
+                let states: Vec<(&Address, &Address, U128, U128)> = source.read().ok()?;
+                //all or nothing, a failed state aborts the whole call
+                let backup = self.balances.clone();
+                for (from, to, token_id, amount) in states.iter() {
+                    if !self.transfer(from, to, token_id.raw(), amount.raw()) {
+                        self.balances = backup;
+                        return None;
+                    }
+                }
+                ret(true)
+            }
+            _ => None,
+        }
+    }
+}
+
+//the oep5 receiver contract, moving tokens through the world like the deployed one
+pub struct FakeReceiver {
+    world: World,
+    this: Address,
+}
+
+impl FakeReceiver {
+    pub fn new(world: &World, this: &Address) -> FakeReceiver {
+        FakeReceiver {
+            world: world.clone(),
+            this: *this,
+        }
+    }
+}
+
+impl FakeContract for FakeReceiver {
+    fn call(&mut self, input: &[u8]) -> Option<Vec<u8>> {
+        let mut source = Source::new(input);
+        let action: &str = source.read().ok()?;
+        let (oep5, to, token_id) = match action {
+            "lockOep5" => {
+                let (oep5, token_id, _): (&Address, U128, bool) = source.read().ok()?;
+                (*oep5, self.this, token_id)
+            }
+            "withdrawOep5" => {
+                let (oep5, to, token_id, _): (&Address, &Address, U128, bool) =
+                    source.read().ok()?;
+                (*oep5, *to, token_id)
+            }
+            _ => return None,
+        };
+        let res = self.world.call_wasm(&oep5, ("transfer", &to, token_id))?;
+        let ok: bool = Source::new(res.as_slice()).read().ok()?;
+        ret(ok)
+    }
+}
+
+//failure injection for the evm fakes, to exercise the bridge's before and after checks
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EvmFailure {
+    None,
+    //mint returns success without changing any balance
+    MintNoop,
+    //mint reverts with Error("mint reverted")
+    MintRevert,
+    //balanceOf returns no data at all
+    EmptyBalance,
+}
+
+impl Default for EvmFailure {
+    fn default() -> Self {
+        EvmFailure::None
+    }
+}
+
+impl EvmFailure {
+    //the response replacing the normal one, None if this call is not affected
+    fn inject(self, sel: [u8; 4], mints: &[[u8; 4]], balance_of: [u8; 4]) -> Option<Vec<u8>> {
+        match self {
+            EvmFailure::MintNoop if mints.contains(&sel) => Some(Vec::new()),
+            EvmFailure::MintRevert if mints.contains(&sel) => Some(encode_call(
+                selector("Error(string)"),
+                &[Token::String(String::from("mint reverted"))],
+            )),
+            EvmFailure::EmptyBalance if sel == balance_of => Some(Vec::new()),
+            _ => None,
+        }
+    }
+}
+
+const INTERFACE_ID_ERC721: [u8; 4] = [0x80, 0xac, 0x58, 0xcd];
+const INTERFACE_ID_ERC1155: [u8; 4] = [0xd9, 0xb6, 0x7a, 0x26];
+
+//splits evm calldata into its selector and arguments
+fn calldata(input: &[u8]) -> Option<([u8; 4], &[u8])> {
+    if input.len() < 4 {
+        return None;
+    }
+    let mut sel = [0u8; 4];
+    sel.copy_from_slice(&input[..4]);
+    Some((sel, &input[4..]))
+}
+
+fn supports_interface(args: &[u8], interface_id: [u8; 4]) -> Option<Vec<u8>> {
+    let mut tokens = decode(&[ParamType::FixedBytes(4)], args).ok()?;
+    let supported = match tokens.remove(0) {
+        Token::FixedBytes(id) => id == interface_id || id == selector("supportsInterface(bytes4)"),
+        _ => false,
+    };
+    Some(encode(&[Token::Bool(supported)]))
+}
+
+#[derive(Default)]
+pub struct FakeErc721 {
+    pub owners: BTreeMap<[u8; 32], Address>,
+    pub failure: EvmFailure,
+}
+
+impl FakeErc721 {
+    pub fn owner_of(&self, token_id: U256) -> Address {
+        self.owners.get(&key(token_id)).cloned().unwrap_or_default()
+    }
+
+    pub fn balance_of(&self, account: &Address) -> u128 {
+        self.owners
+            .values()
+            .filter(|&owner| owner == account)
+            .count() as u128
+    }
+}
+
+impl FakeContract for FakeErc721 {
+    fn call(&mut self, input: &[u8]) -> Option<Vec<u8>> {
+        let (sel, args) = calldata(input)?;
+        let mints = [selector("mint(address,uint256)")];
+        if let Some(res) = self
+            .failure
+            .inject(sel, &mints, selector("balanceOf(address)"))
+        {
+            return Some(res);
+        }
+        if sel == selector("supportsInterface(bytes4)") {
+            return supports_interface(args, INTERFACE_ID_ERC721);
+        }
+        if sel == selector("balanceOf(address)") {
+            let mut tokens = decode(&[ParamType::Address], args).ok()?;
+            let account = tokens.remove(0).into_address()?;
+            return Some(encode(&[uint(self.balance_of(&account))]));
+        }
+        if sel == selector("ownerOf(uint256)") {
+            let mut tokens = decode(&[ParamType::Uint], args).ok()?;
+            let owner = self.owner_of(tokens.remove(0).into_uint()?);
+            return Some(encode(&[Token::Address(owner)]));
+        }
+        if sel == selector("mint(address,uint256)") {
+            let mut tokens = decode(&[ParamType::Address, ParamType::Uint], args).ok()?;
+            let to = tokens.remove(0).into_address()?;
+            let token_id = tokens.remove(0).into_uint()?;
+            if self.owners.contains_key(&key(token_id)) {
+                return None;
+            }
+            self.owners.insert(key(token_id), to);
+            //like many erc721 contracts, mint returns the new token id
+            return Some(encode(&[Token::Uint(token_id)]));
+        }
+        if sel == selector("burn(uint256)") {
+            let mut tokens = decode(&[ParamType::Uint], args).ok()?;
+            self.owners.remove(&key(tokens.remove(0).into_uint()?))?;
+            return Some(Vec::new());
+        }
+        None
+    }
+}
+
+#[derive(Default)]
+pub struct FakeErc1155 {
+    pub balances: BTreeMap<(Address, [u8; 32]), u128>,
+    pub failure: EvmFailure,
+}
+
+impl FakeErc1155 {
+    pub fn balance_of(&self, account: &Address, token_id: U256) -> u128 {
+        self.balances
+            .get(&(*account, key(token_id)))
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    fn mint(&mut self, to: &Address, token_id: U256, value: U256) {
+        *self.balances.entry((*to, key(token_id))).or_insert(0) += amount(value);
+    }
+}
+
+impl FakeContract for FakeErc1155 {
+    fn call(&mut self, input: &[u8]) -> Option<Vec<u8>> {
+        let (sel, args) = calldata(input)?;
+        let mints = [
+            selector("mint(address,uint256,uint256)"),
+            selector("mintBatch(address,uint256[],uint256[],bytes)"),
+        ];
+        if let Some(res) = self
+            .failure
+            .inject(sel, &mints, selector("balanceOf(address,uint256)"))
+        {
+            return Some(res);
+        }
+        if sel == selector("supportsInterface(bytes4)") {
+            return supports_interface(args, INTERFACE_ID_ERC1155);
+        }
+        if sel == selector("balanceOf(address,uint256)") {
+            let mut tokens = decode(&[ParamType::Address, ParamType::Uint], args).ok()?;
+            let account = tokens.remove(0).into_address()?;
+            let token_id = tokens.remove(0).into_uint()?;
+            return Some(encode(&[uint(self.balance_of(&account, token_id))]));
+        }
+        let transfer = [ParamType::Address, ParamType::Uint, ParamType::Uint];
+        if sel == selector("mint(address,uint256,uint256)") {
+            let mut tokens = decode(&transfer, args).ok()?;
+            let to = tokens.remove(0).into_address()?;
+            let token_id = tokens.remove(0).into_uint()?;
+            self.mint(&to, token_id, tokens.remove(0).into_uint()?);
+            return Some(Vec::new());
+        }
+        if sel == selector("mintBatch(address,uint256[],uint256[],bytes)") {
+            let types = [
+                ParamType::Address,
+                ParamType::Array(Box::new(ParamType::Uint)),
+                ParamType::Array(Box::new(ParamType::Uint)),
+                ParamType::Bytes,
+            ];
+            let mut tokens = decode(&types, args).ok()?;
+            let to = tokens.remove(0).into_address()?;
+            let (ids, values) = match (tokens.remove(0), tokens.remove(0)) {
+                (Token::Array(ids), Token::Array(values)) if ids.len() == values.len() => {
+                    (ids, values)
+                }
+                _ => return None,
+            };
+            for (id, value) in ids.into_iter().zip(values.into_iter()) {
+                self.mint(&to, id.into_uint()?, value.into_uint()?);
+            }
+            return Some(Vec::new());
+        }
+        if sel == selector("burn(address,uint256,uint256)") {
+            let mut tokens = decode(&transfer, args).ok()?;
+            let from = tokens.remove(0).into_address()?;
+            let token_id = tokens.remove(0).into_uint()?;
+            let value = amount(tokens.remove(0).into_uint()?);
+            let balance = self.balance_of(&from, token_id);
+            if balance < value {
+                return None;
+            }
+            self.balances.insert((from, key(token_id)), balance - value);
+            return Some(Vec::new());
+        }
+        None
+    }
+}
+
+#[derive(Default)]
+pub struct FakeErc20 {
+    pub balances: BTreeMap<Address, u128>,
+    pub failure: EvmFailure,
+}
+
+impl FakeErc20 {
+    pub fn balance_of(&self, account: &Address) -> u128 {
+        self.balances.get(account).cloned().unwrap_or(0)
+    }
+
+    pub fn total_supply(&self) -> u128 {
+        self.balances.values().sum()
+    }
+}
+
+impl FakeContract for FakeErc20 {
+    fn call(&mut self, input: &[u8]) -> Option<Vec<u8>> {
+        let (sel, args) = calldata(input)?;
+        let mints = [selector("mint(address,uint256)")];
+        if let Some(res) = self
+            .failure
+            .inject(sel, &mints, selector("balanceOf(address)"))
+        {
+            return Some(res);
+        }
+        if sel == selector("balanceOf(address)") {
+            let mut tokens = decode(&[ParamType::Address], args).ok()?;
+            let account = tokens.remove(0).into_address()?;
+            return Some(encode(&[uint(self.balance_of(&account))]));
+        }
+        let transfer = [ParamType::Address, ParamType::Uint];
+        if sel == selector("mint(address,uint256)") {
+            let mut tokens = decode(&transfer, args).ok()?;
+            let to = tokens.remove(0).into_address()?;
+            let value = amount(tokens.remove(0).into_uint()?);
+            *self.balances.entry(to).or_insert(0) += value;
+            return Some(encode(&[Token::Bool(true)]));
+        }
+        //the bridge is treated as approved, allowances are not modelled
+        if sel == selector("burnFrom(address,uint256)") {
+            let mut tokens = decode(&transfer, args).ok()?;
+            let from = tokens.remove(0).into_address()?;
+            let value = amount(tokens.remove(0).into_uint()?);
+            let balance = self.balance_of(&from);
+            if balance < value {
+                return None;
+            }
+            self.balances.insert(from, balance - value);
+            return Some(Vec::new());
+        }
+        None
+    }
+}
+
+const ONT_DECIMALS: u128 = 1_000_000_000;
+
+//native ont, balances are kept with 9 decimals and the v1 methods see whole ont
+#[derive(Default)]
+pub struct FakeOnt {
+    pub balances: BTreeMap<Address, u128>,
+}
+
+impl FakeOnt {
+    pub fn mint(&mut self, to: &Address, amount: u128) {
+        *self.balances.entry(*to).or_insert(0) += amount;
+    }
+
+    pub fn balance_of(&self, account: &Address) -> u128 {
+        self.balances.get(account).cloned().unwrap_or(0)
+    }
+
+    pub fn transfer(&mut self, from: &Address, to: &Address, amount: u128) -> bool {
+        let balance = self.balance_of(from);
+        if balance < amount {
+            return false;
+        }
+        self.balances.insert(*from, balance - amount);
+        self.mint(to, amount);
+        true
+    }
+}
+
+impl FakeContract for FakeOnt {
+    fn call(&mut self, input: &[u8]) -> Option<Vec<u8>> {
+        //native invoke: version, method name and the serialized arguments
+        let mut source = Source::new(input);
+        let _version = source.read_byte().ok()?;
+        let method: &str = source.read().ok()?;
+        let args: &[u8] = source.read().ok()?;
+        let mut args = Source::new(args);
+        let scale = match method {
+            "balanceOf" | "transfer" => ONT_DECIMALS,
+            _ => 1,
+        };
+        match method {
+            "balanceOf" | "balanceOfV2" => {
+                let account = read_native_address(&mut args)?;
+                Some(neo_bytes(self.balance_of(&account) / scale))
+            }
+            "transfer" | "transferV2" => {
+                let count = read_native_uint(&mut args)?;
+                let backup = self.balances.clone();
+                for _ in 0..count {
+                    let from = read_native_address(&mut args)?;
+                    let to = read_native_address(&mut args)?;
+                    let amount = read_native_uint(&mut args)? * scale;
+                    if !self.transfer(&from, &to, amount) {
+                        self.balances = backup;
+                        return None;
+                    }
+                }
+                Some(vec![1])
+            }
+            _ => None,
+        }
+    }
+}
+
+fn read_native_address(source: &mut Source) -> Option<Address> {
+    let bytes: &[u8] = source.read().ok()?;
+    if bytes.len() != 20 {
+        return None;
+    }
+    Some(Address::from_slice(bytes))
+}
+
+fn read_native_uint(source: &mut Source) -> Option<u128> {
+    let bytes: &[u8] = source.read().ok()?;
+    Some(u128_from_neo_bytes(bytes).raw())
+}
+
+//little endian, shortest form, with a sign byte when the top bit is set
+fn neo_bytes(value: u128) -> Vec<u8> {
+    let mut bytes = value.to_le_bytes().to_vec();
+    while bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+    if bytes.last().map_or(false, |&b| b & 0x80 != 0) {
+        bytes.push(0);
+    }
+    bytes
+}
+
+//values passed between wasm and neovm contracts
+#[derive(Clone, Debug, PartialEq)]
+enum NeoValue {
+    Bytes(Vec<u8>),
+    String(String),
+    Address(Address),
+    Bool(bool),
+    Int(u128),
+    List(Vec<NeoValue>),
+}
+
+impl NeoValue {
+    fn into_address(self) -> Option<Address> {
+        match self {
+            NeoValue::Address(addr) => Some(addr),
+            _ => None,
+        }
+    }
+
+    fn into_int(self) -> Option<u128> {
+        match self {
+            NeoValue::Int(value) => Some(value),
+            NeoValue::Bytes(bytes) => Some(u128_from_neo_bytes(&bytes).raw()),
+            _ => None,
+        }
+    }
+}
+
+fn read_neo_value(data: &[u8], pos: &mut usize) -> Option<NeoValue> {
+    fn take<'a>(data: &'a [u8], pos: &mut usize, len: usize) -> Option<&'a [u8]> {
+        let bytes = data.get(*pos..*pos + len)?;
+        *pos += len;
+        Some(bytes)
+    }
+    fn take_u32(data: &[u8], pos: &mut usize) -> Option<usize> {
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(take(data, pos, 4)?);
+        Some(u32::from_le_bytes(buf) as usize)
+    }
+    let tag = take(data, pos, 1)?[0];
+    let value = match tag {
+        0x00 => {
+            let len = take_u32(data, pos)?;
+            NeoValue::Bytes(take(data, pos, len)?.to_vec())
+        }
+        0x01 => {
+            let len = take_u32(data, pos)?;
+            NeoValue::String(String::from_utf8(take(data, pos, len)?.to_vec()).ok()?)
+        }
+        0x02 => NeoValue::Address(Address::from_slice(take(data, pos, 20)?)),
+        0x03 => NeoValue::Bool(take(data, pos, 1)?[0] != 0),
+        0x04 => {
+            let mut buf = [0u8; 16];
+            buf.copy_from_slice(take(data, pos, 16)?);
+            NeoValue::Int(u128::from_le_bytes(buf))
+        }
+        0x10 => {
+            let count = take_u32(data, pos)?;
+            let mut items = Vec::with_capacity(count);
+            for _ in 0..count {
+                items.push(read_neo_value(data, pos)?);
+            }
+            NeoValue::List(items)
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+//a neovm call from wasm: version 0, then the list [method, [args]]
+fn read_neo_call(input: &[u8]) -> Option<(String, Vec<NeoValue>)> {
+    if input.first() != Some(&0) {
+        return None;
+    }
+    let mut pos = 1;
+    let mut call = match read_neo_value(input, &mut pos)? {
+        NeoValue::List(items) if items.len() == 2 => items,
+        _ => return None,
+    };
+    match (call.remove(0), call.remove(0)) {
+        (NeoValue::String(method), NeoValue::List(args)) => Some((method, args)),
+        _ => None,
+    }
+}
+
+fn neo_ret_bool(value: bool) -> Option<Vec<u8>> {
+    Some(vec![0x03, value as u8])
+}
+
+fn neo_ret_int(value: u128) -> Option<Vec<u8>> {
+    let bytes = neo_bytes(value);
+    let mut res = vec![0x00];
+    res.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    res.extend_from_slice(&bytes);
+    Some(res)
+}
+
+//neovm ontd, 1:1 with ont counted in 9 decimals
+pub struct FakeOntd {
+    pub balances: BTreeMap<Address, u128>,
+    //ontd2ont burns ontd but pays no ont out
+    pub silent_ontd2ont: bool,
+    ont: Rc<RefCell<FakeOnt>>,
+    this: Address,
+}
+
+impl FakeOntd {
+    pub fn new(ont: &Rc<RefCell<FakeOnt>>, this: &Address) -> FakeOntd {
+        FakeOntd {
+            balances: BTreeMap::new(),
+            silent_ontd2ont: false,
+            ont: ont.clone(),
+            this: *this,
+        }
+    }
+
+    pub fn balance_of(&self, account: &Address) -> u128 {
+        self.balances.get(account).cloned().unwrap_or(0)
+    }
+
+    pub fn total_supply(&self) -> u128 {
+        self.balances.values().sum()
+    }
+
+    fn burn(&mut self, from: &Address, amount: u128) -> bool {
+        let balance = self.balance_of(from);
+        if balance < amount {
+            return false;
+        }
+        self.balances.insert(*from, balance - amount);
+        true
+    }
+}
+
+impl FakeContract for FakeOntd {
+    fn call(&mut self, input: &[u8]) -> Option<Vec<u8>> {
+        let (method, args) = read_neo_call(input)?;
+        let mut args = args.into_iter();
+        match method.as_str() {
+            "balanceOf" => {
+                let account = args.next()?.into_address()?;
+                neo_ret_int(self.balance_of(&account))
+            }
+            "transfer" => {
+                let from = args.next()?.into_address()?;
+                let to = args.next()?.into_address()?;
+                let amount = args.next()?.into_int()?;
+                if !self.burn(&from, amount) {
+                    return neo_ret_bool(false);
+                }
+                *self.balances.entry(to).or_insert(0) += amount;
+                neo_ret_bool(true)
+            }
+            "ont2ontd" => {
+                let account = args.next()?.into_address()?;
+                let amount = args.next()?.into_int()?;
+                if !self.ont.borrow_mut().transfer(&account, &self.this, amount) {
+                    return neo_ret_bool(false);
+                }
+                *self.balances.entry(account).or_insert(0) += amount;
+                neo_ret_bool(true)
+            }
+            "ontd2ont" => {
+                let account = args.next()?.into_address()?;
+                let amount = args.next()?.into_int()?;
+                if !self.burn(&account, amount) {
+                    return neo_ret_bool(false);
+                }
+                if !self.silent_ontd2ont
+                    && !self.ont.borrow_mut().transfer(&self.this, &account, amount)
+                {
+                    return None;
+                }
+                neo_ret_bool(true)
+            }
+            _ => None,
+        }
+    }
+}
@@ -1,5 +1,6 @@
-use ontio_std::abi::{Decoder, Encoder, Source, VmValueBuilder, VmValueParser};
+use ontio_std::abi::{Decoder, Encoder, Sink, Source, VmValueBuilder, VmValueParser};
 use ontio_std::contract::{ong, ont, wasm};
+use ontio_std::database::{get, put};
 use ontio_std::macros;
 use ontio_std::prelude::Vec;
 use ontio_std::runtime;
@@ -8,6 +9,30 @@ use ontio_std::types::{u128_from_neo_bytes, Address, U128};
 pub const ONT_CONTRACT_ADDRESS: Address = macros::base58!("AFmseVrdL9f9oyCzZefL9tG6UbvhUMqNMV");
 pub const ONG_CONTRACT_ADDRESS: Address = macros::base58!("AFmseVrdL9f9oyCzZefL9tG6UbvhfRZMHJ");
 
+//vm type of the oep5 contracts, kept in the storage of the contract calling them. the chain
+//can not tell the vm of a contract and a call in the encoding of the other vm aborts, so the
+//type is recorded once per contract and every oep5 call below dispatches on it
+const PREFIX_CONTRACT_VM_TYPE: &[u8] = b"16";
+
+fn gen_vm_type_key(contract: &Address) -> Vec<u8> {
+    let mut sink = Sink::new(64);
+    sink.write(PREFIX_CONTRACT_VM_TYPE);
+    sink.write(contract);
+    sink.bytes().to_vec()
+}
+
+pub fn get_contract_vm_type(contract: &Address) -> Option<bool> {
+    get(gen_vm_type_key(contract).as_slice())
+}
+
+pub fn put_contract_vm_type(contract: &Address, is_neovm: bool) {
+    put(gen_vm_type_key(contract), is_neovm);
+}
+
+fn is_neovm_oep5(contract: &Address) -> bool {
+    get_contract_vm_type(contract).expect("unknown contract vm type")
+}
+
 pub fn transfer_oep4(contract: &Address, from: &Address, to: &Address, amount: U128) {
     let mut builder = VmValueBuilder::new();
     builder.string("transfer");
@@ -35,6 +60,19 @@ pub fn ontd_to_ont(contract: &Address, from: &Address, amount: U128) {
     );
 }
 
+pub fn ont_to_ontd(contract: &Address, from: &Address, amount: U128) {
+    let mut builder = VmValueBuilder::new();
+    builder.string("ont2ontd");
+    let mut nested = builder.list();
+    nested.address(from);
+    nested.number(amount);
+    nested.finish();
+    assert!(
+        call_neovm_bool(contract, builder.bytes().as_slice()),
+        "ont2ontd failed"
+    );
+}
+
 pub fn balance_of_oep4(contract: &Address, account: &Address) -> U128 {
     if contract == &ONT_CONTRACT_ADDRESS {
         return ont::balance_of(account);
@@ -50,18 +88,63 @@ pub fn balance_of_oep4(contract: &Address, account: &Address) -> U128 {
     call_neovm_bytearray_num(contract, builder.bytes().as_slice())
 }
 
-pub fn balance_of_oep8(contract: &Address, account: &Address, token_id: U128) -> U128 {
-    call_wasm_contract(contract, ("balanceOf", account, token_id))
+//the v2 helpers count native ont with decimals, the same as ontd, and reach wasm oep4 too
+pub fn transfer_oep4_v2(
+    contract: &Address,
+    from: &Address,
+    to: &Address,
+    amount: U128,
+    oep4_is_neovm: bool,
+) {
+    if contract == &ONT_CONTRACT_ADDRESS {
+        assert!(ont::v2::transfer(from, to, amount), "ont transfer failed");
+    } else if contract == &ONG_CONTRACT_ADDRESS {
+        assert!(ong::transfer(from, to, amount), "ong transfer failed");
+    } else if oep4_is_neovm {
+        transfer_oep4(contract, from, to, amount);
+    } else {
+        let r: bool = call_wasm_contract(contract, ("transfer", from, to, amount));
+        assert!(r, "oep4 transfer failed");
+    }
+}
+
+pub fn balance_of_oep4_v2(contract: &Address, account: &Address, oep4_is_neovm: bool) -> U128 {
+    if contract == &ONT_CONTRACT_ADDRESS {
+        return ont::v2::balance_of(account);
+    }
+    if contract == &ONG_CONTRACT_ADDRESS || oep4_is_neovm {
+        return balance_of_oep4(contract, account);
+    }
+    call_wasm_contract(contract, ("balanceOf", account))
+}
+
+pub fn balance_of_oep8(
+    contract: &Address,
+    account: &Address,
+    token_id: U128,
+    oep8_is_neovm: bool,
+) -> U128 {
+    if oep8_is_neovm {
+        let mut builder = VmValueBuilder::new();
+        builder.string("balanceOf");
+        let mut nested = builder.list();
+        nested.address(account);
+        nested.number(token_id);
+        nested.finish();
+        call_neovm_bytearray_num(contract, builder.bytes().as_slice())
+    } else {
+        call_wasm_contract(contract, ("balanceOf", account, token_id))
+    }
 }
 
-pub fn balance_of_oep5(contract: &Address, account: &Address, oep5_is_neovm: bool) -> U128 {
+pub fn balance_of_oep5(contract: &Address, account: &Address) -> U128 {
     if contract == &ONT_CONTRACT_ADDRESS {
         return ont::balance_of(account);
     }
     if contract == &ONG_CONTRACT_ADDRESS {
         return ong::balance_of(account);
     }
-    if oep5_is_neovm {
+    if is_neovm_oep5(contract) {
         let mut builder = VmValueBuilder::new();
         builder.string("balanceOf");
         let mut nested = builder.list();
@@ -73,8 +156,8 @@ pub fn balance_of_oep5(contract: &Address, account: &Address, oep5_is_neovm: boo
     }
 }
 
-pub fn owner_of(contract: &Address, token_id: U128, oep5_is_neovm: bool) -> Address {
-    if oep5_is_neovm {
+pub fn owner_of(contract: &Address, token_id: U128) -> Address {
+    if is_neovm_oep5(contract) {
         let mut builder = VmValueBuilder::new();
         builder.string("ownerOf");
         let mut nested = builder.list();
@@ -86,25 +169,119 @@ pub fn owner_of(contract: &Address, token_id: U128, oep5_is_neovm: bool) -> Addr
     }
 }
 
+//call balanceOf the way the declared vm expects, false if the result is not a number
+pub fn probe_oep5(contract: &Address, oep5_is_neovm: bool) -> bool {
+    let this = runtime::address();
+    if oep5_is_neovm {
+        let mut builder = VmValueBuilder::new();
+        builder.string("balanceOf");
+        let mut nested = builder.list();
+        nested.address(&this);
+        nested.finish();
+        let result = runtime::call_contract(contract, builder.bytes().as_slice());
+        let mut source = VmValueParser::new(result.as_slice());
+        source.bytearray().is_ok()
+    } else {
+        let result = wasm::call_contract(contract, ("balanceOf", &this));
+        let mut source = Source::new(result.as_slice());
+        let balance: Result<U128, _> = source.read();
+        balance.is_ok()
+    }
+}
+
+pub fn probe_oep8(contract: &Address, oep8_is_neovm: bool) -> bool {
+    let this = runtime::address();
+    if oep8_is_neovm {
+        let mut builder = VmValueBuilder::new();
+        builder.string("balanceOf");
+        let mut nested = builder.list();
+        nested.address(&this);
+        nested.number(U128::new(0));
+        nested.finish();
+        let result = runtime::call_contract(contract, builder.bytes().as_slice());
+        let mut source = VmValueParser::new(result.as_slice());
+        source.bytearray().is_ok()
+    } else {
+        let result = wasm::call_contract(contract, ("balanceOf", &this, U128::new(0)));
+        let mut source = Source::new(result.as_slice());
+        let balance: Result<U128, _> = source.read();
+        balance.is_ok()
+    }
+}
+
 pub fn transfer_oep8(
     contract: &Address,
     from: &Address,
     to: &Address,
     token_id: U128,
     amount: U128,
+    oep8_is_neovm: bool,
 ) {
     if contract == &ONT_CONTRACT_ADDRESS {
         assert!(ont::transfer(from, to, amount), "ont transfer failed");
     } else if contract == &ONG_CONTRACT_ADDRESS {
         assert!(ong::transfer(from, to, amount), "ong transfer failed");
+    } else if oep8_is_neovm {
+        let mut builder = VmValueBuilder::new();
+        builder.string("transfer");
+        let mut nested = builder.list();
+        nested.address(from);
+        nested.address(to);
+        nested.number(token_id);
+        nested.number(amount);
+        nested.finish();
+        assert!(
+            call_neovm_bool(contract, builder.bytes().as_slice()),
+            "oep8 transfer failed"
+        );
     } else {
         let r: bool = call_wasm_contract(contract, ("transfer", from, to, token_id, amount));
         assert!(r, "oep8 transfer failed");
     }
 }
 
-pub fn transfer_oep5(contract: &Address, to: &Address, token_id: U128, oep5_is_neovm: bool) {
-    if oep5_is_neovm {
+//one transferMulti call for many token ids, states are (token_id, amount)
+pub fn transfer_multi_oep8(
+    contract: &Address,
+    from: &Address,
+    to: &Address,
+    states: &[(U128, U128)],
+    oep8_is_neovm: bool,
+) {
+    if contract == &ONT_CONTRACT_ADDRESS || contract == &ONG_CONTRACT_ADDRESS {
+        for &(token_id, amount) in states.iter() {
+            transfer_oep8(contract, from, to, token_id, amount, oep8_is_neovm);
+        }
+    } else if oep8_is_neovm {
+        //neovm oep8 takes every [from, to, tokenId, amount] state as one argument
+        let mut builder = VmValueBuilder::new();
+        builder.string("transferMulti");
+        let mut nested = builder.list();
+        for &(token_id, amount) in states.iter() {
+            let mut state = nested.list();
+            state.address(from);
+            state.address(to);
+            state.number(token_id);
+            state.number(amount);
+            state.finish();
+        }
+        nested.finish();
+        assert!(
+            call_neovm_bool(contract, builder.bytes().as_slice()),
+            "oep8 transferMulti failed"
+        );
+    } else {
+        let states: Vec<(&Address, &Address, U128, U128)> = states
+            .iter()
+            .map(|&(token_id, amount)| (from, to, token_id, amount))
+            .collect();
+        let r: bool = call_wasm_contract(contract, ("transferMulti", states));
+        assert!(r, "oep8 transferMulti failed");
+    }
+}
+
+pub fn transfer_oep5(contract: &Address, to: &Address, token_id: U128) {
+    if is_neovm_oep5(contract) {
         let mut builder = VmValueBuilder::new();
         builder.string("transfer");
         let mut nested = builder.list();
@@ -121,11 +298,22 @@ pub fn transfer_oep5(contract: &Address, to: &Address, token_id: U128, oep5_is_n
     }
 }
 
-pub fn lock_oep5(contract: &Address, oep5: &Address, token_id: U128, oep5_is_neovm: bool) {
+//the receiver keeps its own vm type record, it takes the type recorded here with every call
+pub fn lock_oep5(contract: &Address, oep5: &Address, token_id: U128) {
+    let oep5_is_neovm = is_neovm_oep5(oep5);
     let b: bool = call_wasm_contract(contract, ("lockOep5", oep5, token_id, oep5_is_neovm));
     assert!(b, "lockOep5 failed oep5");
 }
 
+pub fn withdraw_oep5(contract: &Address, oep5: &Address, to: &Address, token_id: U128) {
+    let oep5_is_neovm = is_neovm_oep5(oep5);
+    let b: bool = call_wasm_contract(
+        contract,
+        ("withdrawOep5", oep5, to, token_id, oep5_is_neovm),
+    );
+    assert!(b, "withdrawOep5 failed oep5");
+}
+
 #[track_caller]
 pub fn call_neovm_num(address: &Address, param: &[u8]) -> U128 {
     let result = runtime::call_contract(address, param);
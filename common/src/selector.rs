@@ -0,0 +1,185 @@
+//keccak256 usable in constant context, so that function selectors can be written as signatures
+
+const RATE: usize = 136;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+const ROTATIONS: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+const PI_LANES: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+const fn keccak_f(mut st: [u64; 25]) -> [u64; 25] {
+    let mut round = 0;
+    while round < 24 {
+        //theta
+        let mut bc = [0u64; 5];
+        let mut i = 0;
+        while i < 5 {
+            bc[i] = st[i] ^ st[i + 5] ^ st[i + 10] ^ st[i + 15] ^ st[i + 20];
+            i += 1;
+        }
+        i = 0;
+        while i < 5 {
+            let t = bc[(i + 4) % 5] ^ bc[(i + 1) % 5].rotate_left(1);
+            let mut j = 0;
+            while j < 25 {
+                st[j + i] ^= t;
+                j += 5;
+            }
+            i += 1;
+        }
+        //rho and pi
+        let mut t = st[1];
+        i = 0;
+        while i < 24 {
+            let j = PI_LANES[i];
+            let tmp = st[j];
+            st[j] = t.rotate_left(ROTATIONS[i]);
+            t = tmp;
+            i += 1;
+        }
+        //chi
+        let mut j = 0;
+        while j < 25 {
+            i = 0;
+            while i < 5 {
+                bc[i] = st[j + i];
+                i += 1;
+            }
+            i = 0;
+            while i < 5 {
+                st[j + i] ^= !bc[(i + 1) % 5] & bc[(i + 2) % 5];
+                i += 1;
+            }
+            j += 5;
+        }
+        //iota
+        st[0] ^= ROUND_CONSTANTS[round];
+        round += 1;
+    }
+    st
+}
+
+const fn absorb(mut st: [u64; 25], block: &[u8], pos: usize) -> [u64; 25] {
+    let mut i = 0;
+    while i < RATE / 8 {
+        let mut lane = 0u64;
+        let mut k = 0;
+        while k < 8 {
+            lane |= (block[pos + i * 8 + k] as u64) << (8 * k);
+            k += 1;
+        }
+        st[i] ^= lane;
+        i += 1;
+    }
+    keccak_f(st)
+}
+
+pub const fn keccak256(input: &[u8]) -> [u8; 32] {
+    let mut st = [0u64; 25];
+    let mut pos = 0;
+    while input.len() - pos >= RATE {
+        st = absorb(st, input, pos);
+        pos += RATE;
+    }
+    //keccak padding, not the sha3 one
+    let mut last = [0u8; RATE];
+    let rem = input.len() - pos;
+    let mut i = 0;
+    while i < rem {
+        last[i] = input[pos + i];
+        i += 1;
+    }
+    last[rem] ^= 0x01;
+    last[RATE - 1] ^= 0x80;
+    st = absorb(st, &last, 0);
+
+    let mut out = [0u8; 32];
+    i = 0;
+    while i < 32 {
+        out[i] = (st[i / 8] >> (8 * (i % 8))) as u8;
+        i += 1;
+    }
+    out
+}
+
+//the first 4 bytes of keccak256(signature), e.g. selector("mint(address,uint256)")
+pub const fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+#[macro_export]
+macro_rules! selector {
+    ($signature:expr) => {{
+        const SELECTOR: [u8; 4] = $crate::selector::selector($signature);
+        SELECTOR
+    }};
+}
+
+#[test]
+fn test_keccak256() {
+    assert_eq!(
+        keccak256(b""),
+        [
+            0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7,
+            0x03, 0xc0, 0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04,
+            0x5d, 0x85, 0xa4, 0x70
+        ]
+    );
+    //block boundary and more than one block
+    let long = [b'a'; 200];
+    assert_eq!(
+        keccak256(&long[..135]),
+        [
+            0x34, 0x36, 0x7d, 0xc2, 0x48, 0xbb, 0xd8, 0x32, 0xf4, 0xe3, 0xe6, 0x9d, 0xfa, 0xac,
+            0x2f, 0x92, 0x63, 0x8b, 0xd0, 0xbb, 0xd1, 0x8f, 0x29, 0x12, 0xba, 0x4e, 0xf4, 0x54,
+            0x91, 0x9c, 0xf4, 0x46
+        ]
+    );
+    assert_eq!(
+        keccak256(&long[..136]),
+        [
+            0xa6, 0xc4, 0xd4, 0x03, 0x27, 0x9f, 0xe3, 0xe0, 0xaf, 0x03, 0x72, 0x9c, 0xaa, 0xda,
+            0x83, 0x74, 0xb5, 0xca, 0x54, 0xd8, 0x06, 0x53, 0x29, 0xa3, 0xeb, 0xca, 0xeb, 0x4b,
+            0x60, 0xaa, 0x38, 0x6e
+        ]
+    );
+    assert_eq!(
+        keccak256(&long[..]),
+        [
+            0x96, 0xea, 0x54, 0x06, 0x1d, 0xef, 0x93, 0x6c, 0x4b, 0xe9, 0x0b, 0x51, 0x89, 0x92,
+            0xfd, 0xc6, 0xf1, 0x2f, 0x53, 0x50, 0x68, 0xa2, 0x56, 0x22, 0x9a, 0xca, 0x54, 0x26,
+            0x7b, 0x4d, 0x08, 0x4d
+        ]
+    );
+}
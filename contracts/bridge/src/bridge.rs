@@ -1,11 +1,21 @@
 use crate::events::*;
 use alloc::collections::BTreeMap;
-use common::erc721and1155::{balance_of_erc1155, balance_of_erc721, mint_erc1155, mint_erc721};
-use common::oep5and8::{balance_of_oep5, balance_of_oep8, lock_oep5, owner_of, transfer_oep8};
+use common::erc721and1155::{
+    balance_of_erc1155, balance_of_erc20, balance_of_erc721, burn_erc1155, burn_erc20,
+    burn_erc721, mint_batch_erc1155, mint_erc1155, mint_erc20, mint_erc721, owner_of_erc721,
+    supports_erc1155, supports_erc721,
+};
+use common::ethabi::u256_to_u128;
+use common::oep5and8::{
+    balance_of_oep4_v2, balance_of_oep5, balance_of_oep8, get_contract_vm_type, lock_oep5,
+    owner_of, probe_oep5, probe_oep8, put_contract_vm_type, transfer_multi_oep8,
+    transfer_oep4_v2, transfer_oep8, withdraw_oep5,
+};
 use ostd::abi::{Decoder, Encoder, Sink};
 use ostd::database::{delete, get, put};
 use ostd::prelude::*;
 use ostd::runtime::{address, check_witness, contract_migrate};
+use ostd::types::U256;
 
 const KEY_ADMIN: &[u8] = b"1";
 const KEY_PENDING_ADMIN: &[u8] = b"2";
@@ -14,6 +24,25 @@ const PREFIX_OEP8_ERC1155_PAIR: &[u8] = b"4";
 const KEY_TOKEN_PAIR_NAME: &[u8] = b"5";
 const KEY_RECEIVERS: &[u8] = b"6";
 const PREFIX_OEP8_IDS: &[u8] = b"7";
+const KEY_PAUSED: &[u8] = b"8";
+const KEY_PAUSER: &[u8] = b"9";
+const PREFIX_TOKEN_PAIR_PAUSED: &[u8] = b"10";
+const PREFIX_OEP4_ERC20_PAIR: &[u8] = b"11";
+const KEY_RECEIVER_CAPACITY: &[u8] = b"12";
+const PREFIX_OEP5_RECEIVER_CAPACITY: &[u8] = b"13";
+
+const PREFIX_TOKEN_PAIR_LIMIT: &[u8] = b"14";
+//kept out of TokenPair, pairs stored before the handover existed must still decode
+const PREFIX_PENDING_PAIR_OWNER: &[u8] = b"15";
+//b"16" holds the vm type of the oep contracts, kept by common::oep5and8
+const PREFIX_TOKEN_PAIR_OWNER_PAUSED: &[u8] = b"17";
+//escrow of a pair by token id, the oep contract may be shared with other pairs.
+//oep5 pairs count locked tokens and oep4 pairs count their amount, both under id 0
+const PREFIX_PAIR_ESCROW: &[u8] = b"18";
+//oep8 ids escrowed by a pair, PREFIX_OEP8_IDS keeps them per contract for migrate
+const PREFIX_PAIR_OEP8_IDS: &[u8] = b"19";
+
+const DEFAULT_RECEIVER_CAPACITY: u128 = 1000;
 
 #[derive(Encoder, Decoder, Default)]
 pub struct TokenPair {
@@ -21,7 +50,21 @@ pub struct TokenPair {
     owner: Address,
     erc: Address,
     oep: Address,
-    is_oep5_neovm: bool,
+    is_neovm: bool,
+}
+
+#[derive(Encoder, Decoder, Default)]
+pub struct ReceiverFill {
+    receiver: Address,
+    balance: U128,
+    capacity: U128,
+}
+
+//zero max_amount means no upper limit
+#[derive(Encoder, Decoder, Default)]
+pub struct TokenPairLimit {
+    min_amount: U128,
+    max_amount: U128,
 }
 
 pub fn initialize(admin: &Address) -> bool {
@@ -60,6 +103,87 @@ pub fn accept_admin() -> bool {
     true
 }
 
+pub fn set_pauser(new_pauser: &Address) -> bool {
+    check_admin();
+    let old = get_pauser();
+    put(KEY_PAUSER, new_pauser);
+    set_pauser_event(&old, new_pauser);
+    true
+}
+
+pub fn get_pauser() -> Address {
+    get(KEY_PAUSER).unwrap_or_default()
+}
+
+pub fn pause() -> bool {
+    check_admin_or_pauser();
+    put(KEY_PAUSED, true);
+    pause_event();
+    true
+}
+
+//only admin can resume the bridge, pauser can only stop it
+pub fn unpause() -> bool {
+    check_admin();
+    delete(KEY_PAUSED);
+    unpause_event();
+    true
+}
+
+pub fn is_paused() -> bool {
+    get(KEY_PAUSED).unwrap_or_default()
+}
+
+//a pause by the pair owner is kept apart, the owner can not lift a pause of admin or pauser
+pub fn pause_token_pair(token_pair_name: &[u8]) -> bool {
+    let (pair, _) = get_token_pair_by_name(token_pair_name);
+    let pair: TokenPair = pair.expect("token pair name has not registered");
+    let pauser = get_pauser();
+    let is_pauser = !pauser.is_zero() && check_witness(&pauser);
+    if is_pauser || check_witness(&get_admin()) {
+        put(gen_key(PREFIX_TOKEN_PAIR_PAUSED, token_pair_name), true);
+    } else {
+        check_admin_or_pair_owner(&pair);
+        put(
+            gen_key(PREFIX_TOKEN_PAIR_OWNER_PAUSED, token_pair_name),
+            true,
+        );
+    }
+    pause_token_pair_event(token_pair_name);
+    true
+}
+
+pub fn unpause_token_pair(token_pair_name: &[u8]) -> bool {
+    let (pair, _) = get_token_pair_by_name(token_pair_name);
+    let pair: TokenPair = pair.expect("token pair name has not registered");
+    if check_witness(&get_admin()) {
+        delete(gen_key(PREFIX_TOKEN_PAIR_PAUSED, token_pair_name).as_slice());
+    } else {
+        check_admin_or_pair_owner(&pair);
+        let paused: Option<bool> =
+            get(gen_key(PREFIX_TOKEN_PAIR_PAUSED, token_pair_name).as_slice());
+        assert!(!paused.unwrap_or_default(), "paused by admin");
+    }
+    delete(gen_key(PREFIX_TOKEN_PAIR_OWNER_PAUSED, token_pair_name).as_slice());
+    unpause_token_pair_event(token_pair_name);
+    true
+}
+
+pub fn is_token_pair_paused(token_pair_name: &[u8]) -> bool {
+    let paused: Option<bool> = get(gen_key(PREFIX_TOKEN_PAIR_PAUSED, token_pair_name).as_slice());
+    let owner_paused: Option<bool> =
+        get(gen_key(PREFIX_TOKEN_PAIR_OWNER_PAUSED, token_pair_name).as_slice());
+    paused.unwrap_or_default() || owner_paused.unwrap_or_default()
+}
+
+fn check_not_paused(token_pair_name: &[u8]) {
+    assert!(!is_paused(), "bridge is paused");
+    assert!(
+        !is_token_pair_paused(token_pair_name),
+        "token pair is paused"
+    );
+}
+
 pub fn migrate(
     code: &[u8],
     vm_type: u32,
@@ -73,7 +197,13 @@ pub fn migrate(
     let this = &address();
     let all_token_pair_name = get_all_token_pair_name();
     let mut oep8_id_map: BTreeMap<String, (TokenPair, Vec<U128>)> = BTreeMap::new();
+    let mut oep4_pairs: Vec<TokenPair> = Vec::new();
     for name in all_token_pair_name.iter() {
+        let oep4_pair: Option<TokenPair> = get(gen_token_pair_key_oep4(name.as_bytes()));
+        if let Some(pair) = oep4_pair {
+            oep4_pairs.push(pair);
+            continue;
+        }
         let pair = get_token_pair(name.as_bytes());
         let oep8_ids = get_oep8_ids(&pair.oep);
         if !oep8_ids.is_empty() {
@@ -84,12 +214,20 @@ pub fn migrate(
     let new_addr = contract_migrate(code, vm_type, name, version, author, email, desc);
     assert!(!new_addr.is_zero(), "migrate failed");
     for (_, (pair, ids)) in oep8_id_map.iter() {
-        ids.iter().for_each(|&id| {
-            let oep8_balance = balance_of_oep8(&pair.oep, this, id);
-            if !oep8_balance.is_zero() {
-                transfer_oep8(&pair.oep, this, &new_addr, id, oep8_balance);
-            }
-        });
+        let states: Vec<(U128, U128)> = ids
+            .iter()
+            .map(|&id| (id, balance_of_oep8(&pair.oep, this, id, pair.is_neovm)))
+            .filter(|(_, balance)| !balance.is_zero())
+            .collect();
+        if !states.is_empty() {
+            transfer_multi_oep8(&pair.oep, this, &new_addr, &states, pair.is_neovm);
+        }
+    }
+    for pair in oep4_pairs.iter() {
+        let oep4_balance = balance_of_oep4_v2(&pair.oep, this, pair.is_neovm);
+        if !oep4_balance.is_zero() {
+            transfer_oep4_v2(&pair.oep, this, &new_addr, oep4_balance, pair.is_neovm);
+        }
     }
     true
 }
@@ -98,42 +236,95 @@ pub fn get_all_token_pair_name() -> Vec<String> {
     get(KEY_TOKEN_PAIR_NAME).unwrap_or_default()
 }
 
+//is_neovm can be left out when the vm type of the oep5 contract is recorded already
 pub fn register_oep5_erc721_pair(
     token_pair_name: &str,
     oep5_addr: &Address,
     erc721_addr: &Address,
-    is_neovm: bool,
+    is_neovm: Option<bool>,
 ) -> bool {
-    register_token_pair(token_pair_name, oep5_addr, erc721_addr, true, is_neovm)
+    let is_neovm = is_neovm
+        .or_else(|| get_contract_vm_type(oep5_addr))
+        .expect("unknown contract vm type");
+    register_token_pair(
+        token_pair_name,
+        oep5_addr,
+        erc721_addr,
+        PREFIX_OEP5_ERC721_PAIR,
+        is_neovm,
+    );
+    record_contract_vm_type(oep5_addr, is_neovm);
+    assert!(
+        supports_erc721(&address(), erc721_addr),
+        "erc contract does not support erc721"
+    );
+    assert!(
+        probe_oep5(oep5_addr, is_neovm),
+        "oep contract does not behave as declared oep5"
+    );
+    register_token_pair_evt(token_pair_name, oep5_addr, erc721_addr, true);
+    true
 }
 
 pub fn register_oep8_erc1155_pair(
     token_pair_name: &str,
     oep8_addr: &Address,
     erc1155_addr: &Address,
+    is_neovm: bool,
 ) -> bool {
-    register_token_pair(token_pair_name, oep8_addr, erc1155_addr, false, false)
+    register_token_pair(
+        token_pair_name,
+        oep8_addr,
+        erc1155_addr,
+        PREFIX_OEP8_ERC1155_PAIR,
+        is_neovm,
+    );
+    record_contract_vm_type(oep8_addr, is_neovm);
+    assert!(
+        supports_erc1155(&address(), erc1155_addr),
+        "erc contract does not support erc1155"
+    );
+    assert!(
+        probe_oep8(oep8_addr, is_neovm),
+        "oep contract does not behave as declared oep8"
+    );
+    register_token_pair_evt(token_pair_name, oep8_addr, erc1155_addr, false);
+    true
+}
+
+//is_neovm is not used for native ont and ong
+pub fn register_oep4_erc20_pair(
+    token_pair_name: &str,
+    oep4_addr: &Address,
+    erc20_addr: &Address,
+    is_neovm: bool,
+) -> bool {
+    register_token_pair(
+        token_pair_name,
+        oep4_addr,
+        erc20_addr,
+        PREFIX_OEP4_ERC20_PAIR,
+        is_neovm,
+    );
+    register_oep4_erc20_pair_evt(token_pair_name, oep4_addr, erc20_addr);
+    true
 }
 
 fn register_token_pair(
     token_pair_name: &str,
     oep_addr: &Address,
     erc_addr: &Address,
-    is_oep5: bool,
+    pair_prefix: &[u8],
     is_neovm: bool,
-) -> bool {
+) {
     let admin = get_admin();
     assert!(check_witness(&admin), "need admin signature");
     assert!(!oep_addr.is_zero(), "invalid oep address");
     assert!(!erc_addr.is_zero(), "invalid erc address");
 
-    let pair_key = if is_oep5 {
-        gen_key(PREFIX_OEP5_ERC721_PAIR, token_pair_name)
-    } else {
-        gen_key(PREFIX_OEP8_ERC1155_PAIR, token_pair_name)
-    };
-    let token_pair: Option<TokenPair> = get(pair_key.as_slice());
-    assert!(token_pair.is_none(), "token pair name has registered");
+    let (registered, _) = get_token_pair_by_name(token_pair_name.as_bytes());
+    assert!(registered.is_none(), "token pair name has registered");
+    let pair_key = gen_key(pair_prefix, token_pair_name);
 
     let mut names = get_all_token_pair_name();
     names.push(token_pair_name.to_string());
@@ -145,37 +336,187 @@ fn register_token_pair(
             owner: admin,
             erc: *erc_addr,
             oep: *oep_addr,
-            is_oep5_neovm: is_neovm,
+            is_neovm,
         },
     );
-    register_token_pair_evt(token_pair_name, oep_addr, erc_addr, is_oep5);
+}
+
+pub fn set_pending_pair_owner(token_pair_name: &[u8], new_owner: &Address) -> bool {
+    assert!(!new_owner.is_zero(), "new owner is zero address");
+    let (pair, _) = get_token_pair_by_name(token_pair_name);
+    let pair: TokenPair = pair.expect("token pair name has not registered");
+    check_admin_or_pair_owner(&pair);
+    put(gen_key(PREFIX_PENDING_PAIR_OWNER, token_pair_name), new_owner);
+    set_pending_pair_owner_evt(token_pair_name, new_owner);
     true
 }
 
-//new_owner can be zero address, it means close update function
-pub fn transfer_token_pair_owner(token_pair_name: &[u8], new_owner: &Address) -> bool {
+pub fn get_pending_pair_owner(token_pair_name: &[u8]) -> Address {
+    get(gen_key(PREFIX_PENDING_PAIR_OWNER, token_pair_name).as_slice()).unwrap_or_default()
+}
+
+pub fn accept_pair_owner(token_pair_name: &[u8]) -> bool {
     let (pair, pair_key) = get_token_pair_by_name(token_pair_name);
     let mut pair: TokenPair = pair.expect("token pair name has not registered");
-    let old = pair.owner.clone();
+    let pending_owner = get_pending_pair_owner(token_pair_name);
+    assert!(!pending_owner.is_zero(), "pending owner is zero address");
     assert!(
-        check_witness(&get_admin()) || check_witness(&old),
-        "need admin or owner signature"
+        check_witness(&pending_owner),
+        "check pending owner signature failed"
+    );
+    let old = pair.owner;
+    pair.owner = pending_owner;
+    put(pair_key.as_slice(), pair);
+    delete(gen_key(PREFIX_PENDING_PAIR_OWNER, token_pair_name).as_slice());
+    transfer_token_pair_owner_evt(&old, &pending_owner);
+    true
+}
+
+//after renouncing only admin can manage the token pair
+pub fn renounce_token_pair_owner(token_pair_name: &[u8]) -> bool {
+    let (pair, pair_key) = get_token_pair_by_name(token_pair_name);
+    let mut pair: TokenPair = pair.expect("token pair name has not registered");
+    let old = pair.owner;
+    assert!(!old.is_zero(), "token pair owner has renounced");
+    check_admin_or_pair_owner(&pair);
+    pair.owner = Address::default();
+    put(pair_key.as_slice(), pair);
+    delete(gen_key(PREFIX_PENDING_PAIR_OWNER, token_pair_name).as_slice());
+    transfer_token_pair_owner_evt(&old, &Address::default());
+    true
+}
+
+pub fn set_token_pair_limit(token_pair_name: &[u8], min_amount: U128, max_amount: U128) -> bool {
+    let (pair, _) = get_token_pair_by_name(token_pair_name);
+    let pair: TokenPair = pair.expect("token pair name has not registered");
+    check_admin_or_pair_owner(&pair);
+    assert!(
+        max_amount.is_zero() || min_amount <= max_amount,
+        "min amount is more than max amount"
+    );
+    put(
+        gen_key(PREFIX_TOKEN_PAIR_LIMIT, token_pair_name),
+        TokenPairLimit {
+            min_amount,
+            max_amount,
+        },
+    );
+    set_token_pair_limit_evt(token_pair_name, min_amount, max_amount);
+    true
+}
+
+pub fn get_token_pair_limit(token_pair_name: &[u8]) -> TokenPairLimit {
+    get(gen_key(PREFIX_TOKEN_PAIR_LIMIT, token_pair_name).as_slice()).unwrap_or_default()
+}
+
+fn check_token_pair_limit(token_pair_name: &[u8], amount: U128) {
+    let limit = get_token_pair_limit(token_pair_name);
+    assert!(amount >= limit.min_amount, "amount is less than min amount");
+    assert!(
+        limit.max_amount.is_zero() || amount <= limit.max_amount,
+        "amount is more than max amount"
+    );
+}
+
+pub fn unregister_token_pair(token_pair_name: &[u8]) -> bool {
+    let (pair, pair_key) = get_token_pair_by_name(token_pair_name);
+    let pair: TokenPair = pair.expect("token pair name has not registered");
+    check_admin_or_pair_owner(&pair);
+    assert!(
+        !has_escrowed_asset(&pair, &pair_key, token_pair_name),
+        "token pair has escrowed assets"
     );
-    pair.owner = *new_owner;
-    put(pair_key, pair);
-    transfer_token_pair_owner_evt(&old, new_owner);
+    let mut names = get_all_token_pair_name();
+    names.retain(|name| name.as_bytes() != token_pair_name);
+    put(KEY_TOKEN_PAIR_NAME, names);
+    delete(pair_key.as_slice());
+    delete(gen_key(PREFIX_TOKEN_PAIR_PAUSED, token_pair_name).as_slice());
+    delete(gen_key(PREFIX_TOKEN_PAIR_OWNER_PAUSED, token_pair_name).as_slice());
+    delete(gen_key(PREFIX_TOKEN_PAIR_LIMIT, token_pair_name).as_slice());
+    delete(gen_key(PREFIX_PENDING_PAIR_OWNER, token_pair_name).as_slice());
+    delete(gen_key(PREFIX_PAIR_OEP8_IDS, token_pair_name).as_slice());
+    unregister_token_pair_evt(token_pair_name, &pair.oep, &pair.erc);
     true
 }
 
+pub fn update_token_pair_erc(token_pair_name: &[u8], new_erc: &Address) -> bool {
+    assert!(!new_erc.is_zero(), "invalid erc address");
+    let (pair, pair_key) = get_token_pair_by_name(token_pair_name);
+    let mut pair: TokenPair = pair.expect("token pair name has not registered");
+    check_admin_or_pair_owner(&pair);
+    //tokens minted by the old erc contract could not be bridged back any more
+    assert!(
+        !has_escrowed_asset(&pair, &pair_key, token_pair_name),
+        "token pair has escrowed assets"
+    );
+    if pair_key == gen_token_pair_key_oep5(token_pair_name) {
+        assert!(
+            supports_erc721(&address(), new_erc),
+            "erc contract does not support erc721"
+        );
+    } else if pair_key == gen_token_pair_key_oep8(token_pair_name) {
+        assert!(
+            supports_erc1155(&address(), new_erc),
+            "erc contract does not support erc1155"
+        );
+    }
+    let old_erc = pair.erc;
+    pair.erc = *new_erc;
+    put(pair_key.as_slice(), pair);
+    update_token_pair_erc_evt(token_pair_name, &old_erc, new_erc);
+    true
+}
+
+//escrow taken before it was counted per pair only shows in the balances of the oep contract,
+//they are checked as well when no other pair shares the contract
+fn has_escrowed_asset(pair: &TokenPair, pair_key: &[u8], token_pair_name: &[u8]) -> bool {
+    let is_oep8 = pair_key == gen_token_pair_key_oep8(token_pair_name).as_slice();
+    let escrowed = if is_oep8 {
+        get_pair_oep8_ids(token_pair_name)
+            .iter()
+            .any(|&id| !get_pair_escrow(token_pair_name, id).is_zero())
+    } else {
+        !get_pair_escrow(token_pair_name, U128::new(0)).is_zero()
+    };
+    if escrowed || is_oep_used(&pair.oep, token_pair_name) {
+        return escrowed;
+    }
+    let this = &address();
+    if pair_key == gen_token_pair_key_oep5(token_pair_name).as_slice() {
+        record_pair_vm_type(pair);
+        get_oep5_neovm_receivers()
+            .iter()
+            .any(|r| !balance_of_oep5(&pair.oep, r).is_zero())
+    } else if is_oep8 {
+        get_oep8_ids(&pair.oep)
+            .iter()
+            .any(|&id| !balance_of_oep8(&pair.oep, this, id, pair.is_neovm).is_zero())
+    } else {
+        !balance_of_oep4_v2(&pair.oep, this, pair.is_neovm).is_zero()
+    }
+}
+
+//whether a token pair other than token_pair_name uses the oep contract
+fn is_oep_used(oep: &Address, token_pair_name: &[u8]) -> bool {
+    get_all_token_pair_name()
+        .iter()
+        .filter(|name| name.as_bytes() != token_pair_name)
+        .any(|name| get_token_pair(name.as_bytes()).oep == *oep)
+}
+
 fn get_token_pair_by_name(token_name: &[u8]) -> (Option<TokenPair>, Vec<u8>) {
     let key = gen_token_pair_key_oep5(token_name);
     let pair: Option<TokenPair> = get(key.as_slice());
-    if pair.is_none() {
-        let key = gen_token_pair_key_oep8(token_name);
-        (get(key.as_slice()), key)
-    } else {
-        (pair, key)
+    if pair.is_some() {
+        return (pair, key);
     }
+    let key = gen_token_pair_key_oep8(token_name);
+    let pair: Option<TokenPair> = get(key.as_slice());
+    if pair.is_some() {
+        return (pair, key);
+    }
+    let key = gen_token_pair_key_oep4(token_name);
+    (get(key.as_slice()), key)
 }
 
 pub fn get_oep5_neovm_receivers() -> Vec<Address> {
@@ -190,6 +531,10 @@ fn gen_token_pair_key_oep8(token_name: &[u8]) -> Vec<u8> {
     gen_key(PREFIX_OEP8_ERC1155_PAIR, token_name)
 }
 
+fn gen_token_pair_key_oep4(token_name: &[u8]) -> Vec<u8> {
+    gen_key(PREFIX_OEP4_ERC20_PAIR, token_name)
+}
+
 pub fn get_token_pair(token_name: &[u8]) -> TokenPair {
     let (pair, _) = get_token_pair_by_name(token_name);
     pair.expect("non-exist token pair")
@@ -221,67 +566,555 @@ pub fn del_oep5_neovm_receiver(receiver: &Address) {
     put(KEY_RECEIVERS, addrs);
 }
 
+pub fn set_receiver_capacity(capacity: U128) -> bool {
+    check_admin();
+    assert!(!capacity.is_zero(), "capacity should be more than 0");
+    put(KEY_RECEIVER_CAPACITY, capacity);
+    set_receiver_capacity_event(&Address::default(), capacity);
+    true
+}
+
+pub fn get_receiver_capacity() -> U128 {
+    get(KEY_RECEIVER_CAPACITY).unwrap_or_else(|| U128::new(DEFAULT_RECEIVER_CAPACITY))
+}
+
+//the host does not expose the vm of a contract, and a call encoded for the wrong vm aborts
+//the transaction instead of failing softly. the declared vm is checked with an oep5 balanceOf
+//probe before it is recorded, a record no token pair uses yet can still be corrected
+pub fn set_contract_vm_type(contract: &Address, is_neovm: bool) -> bool {
+    check_admin();
+    if get_contract_vm_type(contract).is_some() {
+        assert!(
+            !is_oep_used(contract, &[]),
+            "contract vm type is used by a token pair"
+        );
+    }
+    assert!(
+        probe_oep5(contract, is_neovm),
+        "oep contract does not behave as declared oep5"
+    );
+    put_contract_vm_type(contract, is_neovm);
+    set_contract_vm_type_event(contract, is_neovm);
+    true
+}
+
+pub fn is_neovm_contract(contract: &Address) -> bool {
+    get_contract_vm_type(contract).expect("unknown contract vm type")
+}
+
+fn record_contract_vm_type(contract: &Address, is_neovm: bool) {
+    match get_contract_vm_type(contract) {
+        Some(recorded) => assert_eq!(
+            recorded, is_neovm,
+            "contract vm type does not match the recorded one"
+        ),
+        None => {
+            put_contract_vm_type(contract, is_neovm);
+            set_contract_vm_type_event(contract, is_neovm);
+        }
+    }
+}
+
+//oep5 pairs registered before the vm type was kept per contract only carry it in the pair
+fn record_pair_vm_type(pair: &TokenPair) {
+    if get_contract_vm_type(&pair.oep).is_none() {
+        put_contract_vm_type(&pair.oep, pair.is_neovm);
+    }
+}
+
+fn get_oep5_pair(token_pair_name: &[u8]) -> TokenPair {
+    let key = gen_token_pair_key_oep5(token_pair_name);
+    let pair: TokenPair = get(key.as_slice()).expect("non-existed token pair name");
+    record_pair_vm_type(&pair);
+    pair
+}
+
+//zero capacity removes the oep5 setting, the global capacity is used instead
+pub fn set_oep5_receiver_capacity(oep5: &Address, capacity: U128) -> bool {
+    check_admin();
+    let key = gen_key(PREFIX_OEP5_RECEIVER_CAPACITY, oep5);
+    if capacity.is_zero() {
+        delete(key.as_slice());
+    } else {
+        put(key.as_slice(), capacity);
+    }
+    set_receiver_capacity_event(oep5, capacity);
+    true
+}
+
+pub fn get_oep5_receiver_capacity(oep5: &Address) -> U128 {
+    get(gen_key(PREFIX_OEP5_RECEIVER_CAPACITY, oep5).as_slice())
+        .unwrap_or_else(get_receiver_capacity)
+}
+
+pub fn get_receiver_fill_levels(token_pair_name: &[u8]) -> Vec<ReceiverFill> {
+    let pair = get_oep5_pair(token_pair_name);
+    let capacity = get_oep5_receiver_capacity(&pair.oep);
+    get_oep5_neovm_receivers()
+        .iter()
+        .map(|receiver| ReceiverFill {
+            receiver: *receiver,
+            balance: balance_of_oep5(&pair.oep, receiver),
+            capacity,
+        })
+        .collect()
+}
+
 pub fn oep5_to_erc721(
     ont_acct: &Address,
     eth_acct: &Address,
-    token_id: U128,
+    token_id: U256,
+    token_pair_name: &[u8],
+) -> bool {
+    oep5_to_erc721_inner(ont_acct, eth_acct, token_id, token_pair_name, false)
+}
+
+//the u256 variants log the erc token id as 32 bytes big endian instead of a number
+pub fn oep5_to_erc721_u256(
+    ont_acct: &Address,
+    eth_acct: &Address,
+    token_id: U256,
+    token_pair_name: &[u8],
+) -> bool {
+    oep5_to_erc721_inner(ont_acct, eth_acct, token_id, token_pair_name, true)
+}
+
+fn oep5_to_erc721_inner(
+    ont_acct: &Address,
+    eth_acct: &Address,
+    token_id: U256,
     token_pair_name: &[u8],
+    u256_event: bool,
 ) -> bool {
+    check_not_paused(token_pair_name);
     assert!(check_witness(ont_acct));
-    let key = gen_token_pair_key_oep5(token_pair_name);
-    let pair: TokenPair = get(key.as_slice()).expect("non-existed token pair name");
+    check_token_pair_limit(token_pair_name, U128::new(1));
+    let oep5_id = to_oep_token_id(token_id);
+    let pair = get_oep5_pair(token_pair_name);
     let this = &address();
-    let (receiver, before) = find_receiver_addr(&pair.oep, pair.is_oep5_neovm);
-    let owner = owner_of(&pair.oep, token_id, pair.is_oep5_neovm);
+    let (receiver, before) = match find_receiver_addr(&pair.oep) {
+        Some(r) => r,
+        None => {
+            receivers_full_event(&pair.oep, get_oep5_receiver_capacity(&pair.oep));
+            return false;
+        }
+    };
+    let owner = owner_of(&pair.oep, oep5_id);
     assert_eq!(ont_acct, &owner, "invalid owner");
-    lock_oep5(&receiver, &pair.oep, token_id, pair.is_oep5_neovm);
-    let after = balance_of_oep5(&pair.oep, &receiver, pair.is_oep5_neovm);
+    lock_oep5(&receiver, &pair.oep, oep5_id);
+    let after = balance_of_oep5(&pair.oep, &receiver);
     let delta = after - before;
     if !delta.is_zero() {
-        let before = balance_of_erc721(this, &pair.erc, eth_acct);
+        let before = to_u128(balance_of_erc721(this, &pair.erc, eth_acct));
         mint_erc721(this, &pair.erc, eth_acct, token_id);
-        let after = balance_of_erc721(this, &pair.erc, eth_acct);
+        let after = to_u128(balance_of_erc721(this, &pair.erc, eth_acct));
         assert_eq!(after - before, U128::new(1), "mint failed");
-        oep5_to_erc721_event(ont_acct, eth_acct, token_id, &pair.oep, &pair.erc);
+        add_pair_escrow(token_pair_name, U128::new(0), U128::new(1));
+        if u256_event {
+            oep5_to_erc721_u256_event(ont_acct, eth_acct, token_id, &pair.oep, &pair.erc);
+        } else {
+            oep5_to_erc721_event(ont_acct, eth_acct, oep5_id, &pair.oep, &pair.erc);
+        }
     }
     true
 }
 
-fn find_receiver_addr(oep5: &Address, oep5_is_neovm: bool) -> (Address, U128) {
+//eth_accts holds one address for all token ids, or one address per token id
+pub fn oep5_to_erc721_batch(
+    ont_acct: &Address,
+    eth_accts: &[Address],
+    token_ids: &[U256],
+    token_pair_name: &[u8],
+) -> bool {
+    oep5_to_erc721_batch_inner(ont_acct, eth_accts, token_ids, token_pair_name, false)
+}
+
+pub fn oep5_to_erc721_batch_u256(
+    ont_acct: &Address,
+    eth_accts: &[Address],
+    token_ids: &[U256],
+    token_pair_name: &[u8],
+) -> bool {
+    oep5_to_erc721_batch_inner(ont_acct, eth_accts, token_ids, token_pair_name, true)
+}
+
+fn oep5_to_erc721_batch_inner(
+    ont_acct: &Address,
+    eth_accts: &[Address],
+    token_ids: &[U256],
+    token_pair_name: &[u8],
+    u256_event: bool,
+) -> bool {
+    check_not_paused(token_pair_name);
+    assert!(check_witness(ont_acct));
+    assert!(!token_ids.is_empty(), "token ids is empty");
+    assert!(
+        eth_accts.len() == 1 || eth_accts.len() == token_ids.len(),
+        "eth accounts length not match token ids"
+    );
+    check_token_pair_limit(token_pair_name, U128::new(token_ids.len() as u128));
+    let pair = get_oep5_pair(token_pair_name);
+    let this = &address();
+    let capacity = get_oep5_receiver_capacity(&pair.oep);
+    let mut receivers: Vec<(Address, U128)> = get_oep5_neovm_receivers()
+        .iter()
+        .map(|r| (*r, balance_of_oep5(&pair.oep, r)))
+        .collect();
+    let free: u128 = receivers
+        .iter()
+        .filter(|(_, bal)| *bal < capacity)
+        .map(|(_, bal)| capacity.raw() - bal.raw())
+        .sum();
+    if free < token_ids.len() as u128 {
+        receivers_full_event(&pair.oep, capacity);
+        return false;
+    }
+    let mut cursor = 0;
+    let mut oep5_ids = Vec::with_capacity(token_ids.len());
+    for (i, &token_id) in token_ids.iter().enumerate() {
+        assert!(!token_ids[..i].contains(&token_id), "duplicate token id");
+        let eth_acct = if eth_accts.len() == 1 {
+            &eth_accts[0]
+        } else {
+            &eth_accts[i]
+        };
+        let oep5_id = to_oep_token_id(token_id);
+        oep5_ids.push(oep5_id);
+        let owner = owner_of(&pair.oep, oep5_id);
+        assert_eq!(ont_acct, &owner, "invalid owner");
+        while receivers[cursor].1 >= capacity {
+            cursor += 1;
+        }
+        let (receiver, before) = receivers[cursor];
+        lock_oep5(&receiver, &pair.oep, oep5_id);
+        let after = balance_of_oep5(&pair.oep, &receiver);
+        assert_eq!(after - before, U128::new(1), "lock oep5 failed");
+        receivers[cursor].1 = after;
+        let before = to_u128(balance_of_erc721(this, &pair.erc, eth_acct));
+        mint_erc721(this, &pair.erc, eth_acct, token_id);
+        let after = to_u128(balance_of_erc721(this, &pair.erc, eth_acct));
+        assert_eq!(after - before, U128::new(1), "mint failed");
+    }
+    let count = U128::new(oep5_ids.len() as u128);
+    add_pair_escrow(token_pair_name, U128::new(0), count);
+    if u256_event {
+        oep5_to_erc721_batch_u256_event(ont_acct, eth_accts, token_ids, &pair.oep, &pair.erc);
+    } else {
+        oep5_to_erc721_batch_event(ont_acct, eth_accts, &oep5_ids, &pair.oep, &pair.erc);
+    }
+    true
+}
+
+pub fn erc721_to_oep5(
+    eth_acct: &Address,
+    ont_acct: &Address,
+    token_id: U256,
+    token_pair_name: &[u8],
+) -> bool {
+    erc721_to_oep5_inner(eth_acct, ont_acct, token_id, token_pair_name, false)
+}
+
+pub fn erc721_to_oep5_u256(
+    eth_acct: &Address,
+    ont_acct: &Address,
+    token_id: U256,
+    token_pair_name: &[u8],
+) -> bool {
+    erc721_to_oep5_inner(eth_acct, ont_acct, token_id, token_pair_name, true)
+}
+
+fn erc721_to_oep5_inner(
+    eth_acct: &Address,
+    ont_acct: &Address,
+    token_id: U256,
+    token_pair_name: &[u8],
+    u256_event: bool,
+) -> bool {
+    check_not_paused(token_pair_name);
+    assert!(check_witness(eth_acct), "check eth account signature failed");
+    check_token_pair_limit(token_pair_name, U128::new(1));
+    let oep5_id = to_oep_token_id(token_id);
+    let pair = get_oep5_pair(token_pair_name);
+    let this = &address();
+    let owner = owner_of_erc721(this, &pair.erc, token_id);
+    assert_eq!(eth_acct, &owner, "invalid owner");
+    //the locked oep5 token is held by the receiver which locked it
+    let receiver = owner_of(&pair.oep, oep5_id);
+    assert!(
+        get_oep5_neovm_receivers().contains(&receiver),
+        "oep5 token is not locked by a bridge receiver"
+    );
+    let before = to_u128(balance_of_erc721(this, &pair.erc, eth_acct));
+    burn_erc721(this, &pair.erc, token_id);
+    let after = to_u128(balance_of_erc721(this, &pair.erc, eth_acct));
+    assert_eq!(before - after, U128::new(1), "burn failed");
+    withdraw_oep5(&receiver, &pair.oep, ont_acct, oep5_id);
+    let owner = owner_of(&pair.oep, oep5_id);
+    assert_eq!(ont_acct, &owner, "withdraw failed");
+    sub_pair_escrow(token_pair_name, U128::new(0), U128::new(1));
+    if u256_event {
+        erc721_to_oep5_u256_event(eth_acct, ont_acct, token_id, &pair.oep, &pair.erc);
+    } else {
+        erc721_to_oep5_event(eth_acct, ont_acct, oep5_id, &pair.oep, &pair.erc);
+    }
+    true
+}
+
+fn find_receiver_addr(oep5: &Address) -> Option<(Address, U128)> {
+    let capacity = get_oep5_receiver_capacity(oep5);
     let receivers = get_oep5_neovm_receivers();
     for item in receivers.iter() {
-        let before = balance_of_oep5(oep5, item, oep5_is_neovm);
-        if before < U128::new(1000) {
-            return (*item, before);
+        let before = balance_of_oep5(oep5, item);
+        if before < capacity {
+            return Some((*item, before));
         }
     }
-    panic!("non-exist address")
+    None
 }
 
 pub fn oep8_to_erc1155(
     ont_acct: &Address,
     eth_acct: &Address,
-    token_id: U128,
+    token_id: U256,
     token_pair_name: &[u8],
     amount: U128,
 ) -> bool {
+    oep8_to_erc1155_inner(ont_acct, eth_acct, token_id, token_pair_name, amount, false)
+}
+
+pub fn oep8_to_erc1155_u256(
+    ont_acct: &Address,
+    eth_acct: &Address,
+    token_id: U256,
+    token_pair_name: &[u8],
+    amount: U128,
+) -> bool {
+    oep8_to_erc1155_inner(ont_acct, eth_acct, token_id, token_pair_name, amount, true)
+}
+
+fn oep8_to_erc1155_inner(
+    ont_acct: &Address,
+    eth_acct: &Address,
+    token_id: U256,
+    token_pair_name: &[u8],
+    amount: U128,
+    u256_event: bool,
+) -> bool {
+    check_not_paused(token_pair_name);
     assert!(check_witness(ont_acct));
     assert!(!amount.is_zero(), "amount should be more than 0");
+    check_token_pair_limit(token_pair_name, amount);
+    let oep8_id = to_oep_token_id(token_id);
     let key = gen_token_pair_key_oep8(token_pair_name);
     let pair: TokenPair = get(key.as_slice()).expect("non-existed token pair name");
     let this = &address();
-    let before = balance_of_oep8(&pair.oep, this, token_id);
-    transfer_oep8(&pair.oep, ont_acct, this, token_id, amount);
-    let after = balance_of_oep8(&pair.oep, this, token_id);
-    push_oep8_id(token_id, &pair.oep);
+    let before = balance_of_oep8(&pair.oep, this, oep8_id, pair.is_neovm);
+    transfer_oep8(&pair.oep, ont_acct, this, oep8_id, amount, pair.is_neovm);
+    let after = balance_of_oep8(&pair.oep, this, oep8_id, pair.is_neovm);
+    push_oep8_id(oep8_id, &pair.oep);
     let delta = after - before;
     if !delta.is_zero() {
-        let before = balance_of_erc1155(this, &pair.erc, eth_acct, token_id);
-        mint_erc1155(this, &pair.erc, eth_acct, token_id, amount);
-        let after = balance_of_erc1155(this, &pair.erc, eth_acct, token_id);
+        let before = to_u128(balance_of_erc1155(this, &pair.erc, eth_acct, token_id));
+        mint_erc1155(this, &pair.erc, eth_acct, token_id, U256::from(amount));
+        let after = to_u128(balance_of_erc1155(this, &pair.erc, eth_acct, token_id));
         assert_eq!(after - before, amount, "mint failed");
+        push_pair_oep8_id(token_pair_name, oep8_id);
+        add_pair_escrow(token_pair_name, oep8_id, amount);
+    }
+    let (oep, erc) = (&pair.oep, &pair.erc);
+    if u256_event {
+        oep8_to_erc1155_u256_event(ont_acct, eth_acct, token_id, amount, oep, erc);
+    } else {
+        oep8_to_erc1155_event(ont_acct, eth_acct, oep8_id, amount, oep, erc);
     }
-    oep8_to_erc1155_event(ont_acct, eth_acct, token_id, amount, &pair.oep, &pair.erc);
+    true
+}
+
+pub fn oep8_to_erc1155_batch(
+    ont_acct: &Address,
+    eth_acct: &Address,
+    token_ids: &[U256],
+    amounts: &[U128],
+    token_pair_name: &[u8],
+) -> bool {
+    oep8_to_erc1155_batch_inner(ont_acct, eth_acct, token_ids, amounts, token_pair_name, false)
+}
+
+pub fn oep8_to_erc1155_batch_u256(
+    ont_acct: &Address,
+    eth_acct: &Address,
+    token_ids: &[U256],
+    amounts: &[U128],
+    token_pair_name: &[u8],
+) -> bool {
+    oep8_to_erc1155_batch_inner(ont_acct, eth_acct, token_ids, amounts, token_pair_name, true)
+}
+
+fn oep8_to_erc1155_batch_inner(
+    ont_acct: &Address,
+    eth_acct: &Address,
+    token_ids: &[U256],
+    amounts: &[U128],
+    token_pair_name: &[u8],
+    u256_event: bool,
+) -> bool {
+    check_not_paused(token_pair_name);
+    assert!(check_witness(ont_acct));
+    assert!(!token_ids.is_empty(), "token ids is empty");
+    assert_eq!(
+        token_ids.len(),
+        amounts.len(),
+        "token ids length not match amounts"
+    );
+    let key = gen_token_pair_key_oep8(token_pair_name);
+    let pair: TokenPair = get(key.as_slice()).expect("non-existed token pair name");
+    let this = &address();
+    let mut states: Vec<(U128, U128)> = Vec::with_capacity(token_ids.len());
+    for (i, (&token_id, &amount)) in token_ids.iter().zip(amounts.iter()).enumerate() {
+        assert!(!token_ids[..i].contains(&token_id), "duplicate token id");
+        assert!(!amount.is_zero(), "amount should be more than 0");
+        check_token_pair_limit(token_pair_name, amount);
+        states.push((to_oep_token_id(token_id), amount));
+    }
+    let before: Vec<U128> = states
+        .iter()
+        .map(|&(id, _)| balance_of_oep8(&pair.oep, this, id, pair.is_neovm))
+        .collect();
+    transfer_multi_oep8(&pair.oep, ont_acct, this, &states, pair.is_neovm);
+    for (i, &(id, amount)) in states.iter().enumerate() {
+        let after = balance_of_oep8(&pair.oep, this, id, pair.is_neovm);
+        assert_eq!(after - before[i], amount, "transfer oep8 failed");
+        push_oep8_id(id, &pair.oep);
+    }
+    let before: Vec<U128> = token_ids
+        .iter()
+        .map(|&id| to_u128(balance_of_erc1155(this, &pair.erc, eth_acct, id)))
+        .collect();
+    let erc_amounts: Vec<U256> = amounts.iter().map(|&amt| U256::from(amt)).collect();
+    mint_batch_erc1155(this, &pair.erc, eth_acct, token_ids, erc_amounts.as_slice());
+    for (i, &token_id) in token_ids.iter().enumerate() {
+        let after = to_u128(balance_of_erc1155(this, &pair.erc, eth_acct, token_id));
+        assert_eq!(after - before[i], amounts[i], "mint failed");
+    }
+    for &(id, amount) in states.iter() {
+        push_pair_oep8_id(token_pair_name, id);
+        add_pair_escrow(token_pair_name, id, amount);
+    }
+    let (oep, erc) = (&pair.oep, &pair.erc);
+    if u256_event {
+        oep8_to_erc1155_batch_u256_event(ont_acct, eth_acct, token_ids, amounts, oep, erc);
+    } else {
+        let oep8_ids: Vec<U128> = states.iter().map(|&(id, _)| id).collect();
+        oep8_to_erc1155_batch_event(ont_acct, eth_acct, &oep8_ids, amounts, oep, erc);
+    }
+    true
+}
+
+pub fn erc1155_to_oep8(
+    eth_acct: &Address,
+    ont_acct: &Address,
+    token_id: U256,
+    token_pair_name: &[u8],
+    amount: U128,
+) -> bool {
+    erc1155_to_oep8_inner(eth_acct, ont_acct, token_id, token_pair_name, amount, false)
+}
+
+pub fn erc1155_to_oep8_u256(
+    eth_acct: &Address,
+    ont_acct: &Address,
+    token_id: U256,
+    token_pair_name: &[u8],
+    amount: U128,
+) -> bool {
+    erc1155_to_oep8_inner(eth_acct, ont_acct, token_id, token_pair_name, amount, true)
+}
+
+fn erc1155_to_oep8_inner(
+    eth_acct: &Address,
+    ont_acct: &Address,
+    token_id: U256,
+    token_pair_name: &[u8],
+    amount: U128,
+    u256_event: bool,
+) -> bool {
+    check_not_paused(token_pair_name);
+    assert!(check_witness(eth_acct), "check eth account signature failed");
+    assert!(!amount.is_zero(), "amount should be more than 0");
+    check_token_pair_limit(token_pair_name, amount);
+    let oep8_id = to_oep_token_id(token_id);
+    let key = gen_token_pair_key_oep8(token_pair_name);
+    let pair: TokenPair = get(key.as_slice()).expect("non-existed token pair name");
+    let this = &address();
+    let before = to_u128(balance_of_erc1155(this, &pair.erc, eth_acct, token_id));
+    assert!(before >= amount, "insufficient erc1155 balance");
+    burn_erc1155(this, &pair.erc, eth_acct, token_id, U256::from(amount));
+    let after = to_u128(balance_of_erc1155(this, &pair.erc, eth_acct, token_id));
+    assert_eq!(before - after, amount, "burn failed");
+    let before = balance_of_oep8(&pair.oep, ont_acct, oep8_id, pair.is_neovm);
+    transfer_oep8(&pair.oep, this, ont_acct, oep8_id, amount, pair.is_neovm);
+    let after = balance_of_oep8(&pair.oep, ont_acct, oep8_id, pair.is_neovm);
+    assert_eq!(after - before, amount, "release oep8 failed");
+    sub_pair_escrow(token_pair_name, oep8_id, amount);
+    let (oep, erc) = (&pair.oep, &pair.erc);
+    if u256_event {
+        erc1155_to_oep8_u256_event(eth_acct, ont_acct, token_id, amount, oep, erc);
+    } else {
+        erc1155_to_oep8_event(eth_acct, ont_acct, oep8_id, amount, oep, erc);
+    }
+    true
+}
+
+pub fn oep4_to_erc20(
+    ont_acct: &Address,
+    eth_acct: &Address,
+    amount: U128,
+    token_pair_name: &[u8],
+) -> bool {
+    check_not_paused(token_pair_name);
+    assert!(check_witness(ont_acct));
+    assert!(!amount.is_zero(), "amount should be more than 0");
+    check_token_pair_limit(token_pair_name, amount);
+    let key = gen_token_pair_key_oep4(token_pair_name);
+    let pair: TokenPair = get(key.as_slice()).expect("non-existed token pair name");
+    let this = &address();
+    let before = balance_of_oep4_v2(&pair.oep, this, pair.is_neovm);
+    transfer_oep4_v2(&pair.oep, ont_acct, this, amount, pair.is_neovm);
+    let after = balance_of_oep4_v2(&pair.oep, this, pair.is_neovm);
+    assert_eq!(after - before, amount, "transfer oep4 failed");
+    let before = to_u128(balance_of_erc20(this, &pair.erc, eth_acct));
+    mint_erc20(this, &pair.erc, eth_acct, U256::from(amount));
+    let after = to_u128(balance_of_erc20(this, &pair.erc, eth_acct));
+    assert_eq!(after - before, amount, "mint failed");
+    add_pair_escrow(token_pair_name, U128::new(0), amount);
+    oep4_to_erc20_event(ont_acct, eth_acct, amount, &pair.oep, &pair.erc);
+    true
+}
+
+pub fn erc20_to_oep4(
+    eth_acct: &Address,
+    ont_acct: &Address,
+    amount: U128,
+    token_pair_name: &[u8],
+) -> bool {
+    check_not_paused(token_pair_name);
+    assert!(check_witness(eth_acct), "check eth account signature failed");
+    assert!(!amount.is_zero(), "amount should be more than 0");
+    check_token_pair_limit(token_pair_name, amount);
+    let key = gen_token_pair_key_oep4(token_pair_name);
+    let pair: TokenPair = get(key.as_slice()).expect("non-existed token pair name");
+    let this = &address();
+    let before = to_u128(balance_of_erc20(this, &pair.erc, eth_acct));
+    assert!(before >= amount, "insufficient erc20 balance");
+    burn_erc20(this, &pair.erc, eth_acct, U256::from(amount));
+    let after = to_u128(balance_of_erc20(this, &pair.erc, eth_acct));
+    assert_eq!(before - after, amount, "burn failed");
+    let before = balance_of_oep4_v2(&pair.oep, ont_acct, pair.is_neovm);
+    transfer_oep4_v2(&pair.oep, this, ont_acct, amount, pair.is_neovm);
+    let after = balance_of_oep4_v2(&pair.oep, ont_acct, pair.is_neovm);
+    assert_eq!(after - before, amount, "release oep4 failed");
+    sub_pair_escrow(token_pair_name, U128::new(0), amount);
+    erc20_to_oep4_event(eth_acct, ont_acct, amount, &pair.oep, &pair.erc);
     true
 }
 
@@ -298,10 +1131,66 @@ fn get_oep8_ids(oep8: &Address) -> Vec<U128> {
     get(key.as_slice()).unwrap_or_default()
 }
 
+fn push_pair_oep8_id(token_pair_name: &[u8], id: U128) {
+    let mut ids = get_pair_oep8_ids(token_pair_name);
+    if !ids.contains(&id) {
+        ids.push(id);
+        put(gen_key(PREFIX_PAIR_OEP8_IDS, token_pair_name), ids);
+    }
+}
+
+fn get_pair_oep8_ids(token_pair_name: &[u8]) -> Vec<U128> {
+    get(gen_key(PREFIX_PAIR_OEP8_IDS, token_pair_name).as_slice()).unwrap_or_default()
+}
+
+fn get_pair_escrow(token_pair_name: &[u8], id: U128) -> U128 {
+    get(gen_key(PREFIX_PAIR_ESCROW, (token_pair_name, id)).as_slice()).unwrap_or_default()
+}
+
+fn add_pair_escrow(token_pair_name: &[u8], id: U128, amount: U128) {
+    let escrow = get_pair_escrow(token_pair_name, id) + amount;
+    put(gen_key(PREFIX_PAIR_ESCROW, (token_pair_name, id)), escrow);
+}
+
+//escrow from before the per pair accounting is not counted, release it without going below 0
+fn sub_pair_escrow(token_pair_name: &[u8], id: U128, amount: U128) {
+    let escrow = get_pair_escrow(token_pair_name, id);
+    let key = gen_key(PREFIX_PAIR_ESCROW, (token_pair_name, id));
+    if escrow > amount {
+        put(key, escrow - amount);
+    } else {
+        delete(key.as_slice());
+    }
+}
+
+//oep5 and oep8 contracts only support 128 bits token id
+fn to_oep_token_id(token_id: U256) -> U128 {
+    u256_to_u128(token_id).expect("token id is out of oep range")
+}
+
+pub fn to_u128(value: U256) -> U128 {
+    u256_to_u128(value).expect("erc balance is out of range")
+}
+
 fn check_admin() {
     assert!(check_witness(&get_admin()), "check admin signature failed");
 }
 
+fn check_admin_or_pair_owner(pair: &TokenPair) {
+    assert!(
+        check_witness(&get_admin()) || (!pair.owner.is_zero() && check_witness(&pair.owner)),
+        "need admin or owner signature"
+    );
+}
+
+fn check_admin_or_pauser() {
+    let pauser = get_pauser();
+    assert!(
+        check_witness(&get_admin()) || (!pauser.is_zero() && check_witness(&pauser)),
+        "need admin or pauser signature"
+    );
+}
+
 fn gen_key<T: Encoder>(prefix: &[u8], post: T) -> Vec<u8> {
     let mut sink = Sink::new(64);
     sink.write(prefix);
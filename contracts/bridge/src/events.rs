@@ -1,4 +1,4 @@
-use crate::{Address, U128};
+use crate::{Address, U128, U256};
 use ontio_std::abi::EventBuilder;
 
 pub fn new_pending_admin_event(new_pending_admin: &Address) {
@@ -16,6 +16,36 @@ pub fn new_admin_event(old_admin: &Address, new_pending_admin: &Address) {
         .notify();
 }
 
+pub fn set_pauser_event(old_pauser: &Address, new_pauser: &Address) {
+    EventBuilder::new()
+        .string("setPauser")
+        .address(old_pauser)
+        .address(new_pauser)
+        .notify();
+}
+
+pub fn pause_event() {
+    EventBuilder::new().string("pause").notify();
+}
+
+pub fn unpause_event() {
+    EventBuilder::new().string("unpause").notify();
+}
+
+pub fn pause_token_pair_event(token_pair_name: &[u8]) {
+    EventBuilder::new()
+        .string("pauseTokenPair")
+        .bytearray(token_pair_name)
+        .notify();
+}
+
+pub fn unpause_token_pair_event(token_pair_name: &[u8]) {
+    EventBuilder::new()
+        .string("unpauseTokenPair")
+        .bytearray(token_pair_name)
+        .notify();
+}
+
 pub fn register_token_pair_evt(
     token_pair_name: &str,
     oep_addr: &Address,
@@ -31,6 +61,54 @@ pub fn register_token_pair_evt(
         .notify();
 }
 
+pub fn register_oep4_erc20_pair_evt(
+    token_pair_name: &str,
+    oep4_addr: &Address,
+    erc20_addr: &Address,
+) {
+    EventBuilder::new()
+        .string("registerOep4Erc20Pair")
+        .string(token_pair_name)
+        .address(oep4_addr)
+        .address(erc20_addr)
+        .notify();
+}
+
+pub fn unregister_token_pair_evt(token_pair_name: &[u8], oep_addr: &Address, erc_addr: &Address) {
+    EventBuilder::new()
+        .string("unregisterTokenPair")
+        .bytearray(token_pair_name)
+        .address(oep_addr)
+        .address(erc_addr)
+        .notify();
+}
+
+pub fn update_token_pair_erc_evt(token_pair_name: &[u8], old_erc: &Address, new_erc: &Address) {
+    EventBuilder::new()
+        .string("updateTokenPairErc")
+        .bytearray(token_pair_name)
+        .address(old_erc)
+        .address(new_erc)
+        .notify();
+}
+
+pub fn set_token_pair_limit_evt(token_pair_name: &[u8], min_amount: U128, max_amount: U128) {
+    EventBuilder::new()
+        .string("setTokenPairLimit")
+        .bytearray(token_pair_name)
+        .number(min_amount)
+        .number(max_amount)
+        .notify();
+}
+
+pub fn set_pending_pair_owner_evt(token_pair_name: &[u8], new_pending_owner: &Address) {
+    EventBuilder::new()
+        .string("setPendingPairOwner")
+        .bytearray(token_pair_name)
+        .address(new_pending_owner)
+        .notify();
+}
+
 pub fn transfer_token_pair_owner_evt(old_owner: &Address, new_owner: &Address) {
     EventBuilder::new()
         .string("transferTokenPairOwner")
@@ -39,6 +117,31 @@ pub fn transfer_token_pair_owner_evt(old_owner: &Address, new_owner: &Address) {
         .notify();
 }
 
+//oep5 is zero address when the global capacity is changed
+pub fn set_receiver_capacity_event(oep5_addr: &Address, capacity: U128) {
+    EventBuilder::new()
+        .string("setReceiverCapacity")
+        .address(oep5_addr)
+        .number(capacity)
+        .notify();
+}
+
+pub fn set_contract_vm_type_event(contract: &Address, is_neovm: bool) {
+    EventBuilder::new()
+        .string("setContractVmType")
+        .address(contract)
+        .bool(is_neovm)
+        .notify();
+}
+
+pub fn receivers_full_event(oep5_addr: &Address, capacity: U128) {
+    EventBuilder::new()
+        .string("receiversFull")
+        .address(oep5_addr)
+        .number(capacity)
+        .notify();
+}
+
 pub fn oep5_to_erc721_event(
     ont_acct: &Address,
     eth_acct: &Address,
@@ -56,6 +159,106 @@ pub fn oep5_to_erc721_event(
         .notify();
 }
 
+//the u256 events carry the erc token id as 32 bytes big endian
+pub fn oep5_to_erc721_u256_event(
+    ont_acct: &Address,
+    eth_acct: &Address,
+    token_id: U256,
+    oep5_addr: &Address,
+    erc721_addr: &Address,
+) {
+    EventBuilder::new()
+        .string("oep5ToOrc721U256")
+        .address(ont_acct)
+        .address(eth_acct)
+        .bytearray(&token_id.to_be_bytes())
+        .address(oep5_addr)
+        .address(erc721_addr)
+        .notify();
+}
+
+pub fn oep5_to_erc721_batch_event(
+    ont_acct: &Address,
+    eth_accts: &[Address],
+    token_ids: &[U128],
+    oep5_addr: &Address,
+    erc721_addr: &Address,
+) {
+    let mut builder = EventBuilder::new()
+        .string("oep5ToOrc721Batch")
+        .address(ont_acct)
+        .address(oep5_addr)
+        .address(erc721_addr)
+        .number(U128::new(token_ids.len() as u128));
+    for (i, token_id) in token_ids.iter().enumerate() {
+        let eth_acct = if eth_accts.len() == 1 {
+            &eth_accts[0]
+        } else {
+            &eth_accts[i]
+        };
+        builder = builder.address(eth_acct).number(*token_id);
+    }
+    builder.notify();
+}
+
+pub fn oep5_to_erc721_batch_u256_event(
+    ont_acct: &Address,
+    eth_accts: &[Address],
+    token_ids: &[U256],
+    oep5_addr: &Address,
+    erc721_addr: &Address,
+) {
+    let mut builder = EventBuilder::new()
+        .string("oep5ToOrc721BatchU256")
+        .address(ont_acct)
+        .address(oep5_addr)
+        .address(erc721_addr)
+        .number(U128::new(token_ids.len() as u128));
+    for (i, token_id) in token_ids.iter().enumerate() {
+        let eth_acct = if eth_accts.len() == 1 {
+            &eth_accts[0]
+        } else {
+            &eth_accts[i]
+        };
+        builder = builder.address(eth_acct).bytearray(&token_id.to_be_bytes());
+    }
+    builder.notify();
+}
+
+pub fn erc721_to_oep5_event(
+    eth_acct: &Address,
+    ont_acct: &Address,
+    token_id: U128,
+    oep5_addr: &Address,
+    erc721_addr: &Address,
+) {
+    EventBuilder::new()
+        .string("orc721ToOep5")
+        .address(eth_acct)
+        .address(ont_acct)
+        .number(token_id)
+        .address(oep5_addr)
+        .address(erc721_addr)
+        .notify();
+}
+
+pub fn erc721_to_oep5_u256_event(
+    eth_acct: &Address,
+    ont_acct: &Address,
+    token_id: U256,
+    oep5_addr: &Address,
+    erc721_addr: &Address,
+) {
+    EventBuilder::new()
+        .string("orc721ToOep5U256")
+        .address(eth_acct)
+        .address(ont_acct)
+        .bytearray(&token_id.to_be_bytes())
+        .address(oep5_addr)
+        .address(erc721_addr)
+        .notify();
+}
+
 pub fn oep8_to_erc1155_event(
     ont_acct: &Address,
     eth_acct: &Address,
@@ -74,3 +277,136 @@ pub fn oep8_to_erc1155_event(
         .address(erc1155_addr)
         .notify();
 }
+
+pub fn oep8_to_erc1155_u256_event(
+    ont_acct: &Address,
+    eth_acct: &Address,
+    token_id: U256,
+    amount: U128,
+    oep8_addr: &Address,
+    erc1155_addr: &Address,
+) {
+    EventBuilder::new()
+        .string("oep8ToOrc1155U256")
+        .address(ont_acct)
+        .address(eth_acct)
+        .bytearray(&token_id.to_be_bytes())
+        .number(amount)
+        .address(oep8_addr)
+        .address(erc1155_addr)
+        .notify();
+}
+
+pub fn oep8_to_erc1155_batch_event(
+    ont_acct: &Address,
+    eth_acct: &Address,
+    token_ids: &[U128],
+    amounts: &[U128],
+    oep8_addr: &Address,
+    erc1155_addr: &Address,
+) {
+    let mut builder = EventBuilder::new()
+        .string("oep8ToOrc1155Batch")
+        .address(ont_acct)
+        .address(eth_acct)
+        .address(oep8_addr)
+        .address(erc1155_addr)
+        .number(U128::new(token_ids.len() as u128));
+    for (token_id, amount) in token_ids.iter().zip(amounts.iter()) {
+        builder = builder.number(*token_id).number(*amount);
+    }
+    builder.notify();
+}
+
+pub fn oep8_to_erc1155_batch_u256_event(
+    ont_acct: &Address,
+    eth_acct: &Address,
+    token_ids: &[U256],
+    amounts: &[U128],
+    oep8_addr: &Address,
+    erc1155_addr: &Address,
+) {
+    let mut builder = EventBuilder::new()
+        .string("oep8ToOrc1155BatchU256")
+        .address(ont_acct)
+        .address(eth_acct)
+        .address(oep8_addr)
+        .address(erc1155_addr)
+        .number(U128::new(token_ids.len() as u128));
+    for (token_id, amount) in token_ids.iter().zip(amounts.iter()) {
+        builder = builder.bytearray(&token_id.to_be_bytes()).number(*amount);
+    }
+    builder.notify();
+}
+
+pub fn erc1155_to_oep8_event(
+    eth_acct: &Address,
+    ont_acct: &Address,
+    token_id: U128,
+    amount: U128,
+    oep8_addr: &Address,
+    erc1155_addr: &Address,
+) {
+    EventBuilder::new()
+        .string("orc1155ToOep8")
+        .address(eth_acct)
+        .address(ont_acct)
+        .number(token_id)
+        .number(amount)
+        .address(oep8_addr)
+        .address(erc1155_addr)
+        .notify();
+}
+
+pub fn erc1155_to_oep8_u256_event(
+    eth_acct: &Address,
+    ont_acct: &Address,
+    token_id: U256,
+    amount: U128,
+    oep8_addr: &Address,
+    erc1155_addr: &Address,
+) {
+    EventBuilder::new()
+        .string("orc1155ToOep8U256")
+        .address(eth_acct)
+        .address(ont_acct)
+        .bytearray(&token_id.to_be_bytes())
+        .number(amount)
+        .address(oep8_addr)
+        .address(erc1155_addr)
+        .notify();
+}
+
+pub fn oep4_to_erc20_event(
+    ont_acct: &Address,
+    eth_acct: &Address,
+    amount: U128,
+    oep4_addr: &Address,
+    erc20_addr: &Address,
+) {
+    EventBuilder::new()
+        .string("oep4ToOrc20")
+        .address(ont_acct)
+        .address(eth_acct)
+        .number(amount)
+        .address(oep4_addr)
+        .address(erc20_addr)
+        .notify();
+}
+
+pub fn erc20_to_oep4_event(
+    eth_acct: &Address,
+    ont_acct: &Address,
+    amount: U128,
+    oep4_addr: &Address,
+    erc20_addr: &Address,
+) {
+    EventBuilder::new()
+        .string("orc20ToOep4")
+        .address(eth_acct)
+        .address(ont_acct)
+        .number(amount)
+        .address(oep4_addr)
+        .address(erc20_addr)
+        .notify();
+}
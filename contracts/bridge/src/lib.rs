@@ -5,21 +5,30 @@ extern crate ontio_std as ostd;
 
 use crate::bridge::*;
 use common::erc721and1155::{balance_of_erc1155, balance_of_erc721, mint_erc1155, mint_erc721};
+use common::ethabi::u256_from_be_slice;
 use common::oep5and8::balance_of_oep5;
 use ostd::abi::{Sink, Source};
 use ostd::prelude::*;
 use ostd::runtime::{address, input, ret};
+use ostd::types::U256;
 
 extern crate alloc;
 extern crate common;
 
 mod bridge;
 mod events;
+#[cfg(all(test, feature = "mock"))]
+mod test;
 
-#[no_mangle]
+//left unexported in mock builds, contracts linking the bridge in tests have their own invoke
+#[cfg_attr(not(feature = "mock"), no_mangle)]
 pub fn invoke() {
     let input = input();
-    let mut source = Source::new(&input);
+    ret(&dispatch(&input))
+}
+
+pub fn dispatch(input: &[u8]) -> Vec<u8> {
+    let mut source = Source::new(input);
     let action = source.read().unwrap();
     let mut sink = Sink::new(12);
     match action {
@@ -40,6 +49,34 @@ pub fn invoke() {
         "acceptAdmin" => {
             sink.write(accept_admin());
         }
+        "setPauser" => {
+            let new_pauser = source.read().unwrap();
+            sink.write(set_pauser(new_pauser));
+        }
+        "getPauser" => {
+            sink.write(get_pauser());
+        }
+        "pause" => {
+            sink.write(pause());
+        }
+        "unpause" => {
+            sink.write(unpause());
+        }
+        "isPaused" => {
+            sink.write(is_paused());
+        }
+        "pauseTokenPair" => {
+            let token_pair_name = source.read().unwrap();
+            sink.write(pause_token_pair(token_pair_name));
+        }
+        "unpauseTokenPair" => {
+            let token_pair_name = source.read().unwrap();
+            sink.write(unpause_token_pair(token_pair_name));
+        }
+        "isTokenPairPaused" => {
+            let token_pair_name = source.read().unwrap();
+            sink.write(is_token_pair_paused(token_pair_name));
+        }
         "migrate" => {
             let (code, vm_type, name, version, author, email, desc) = source.read().unwrap();
             let vm_type: U128 = vm_type;
@@ -62,8 +99,37 @@ pub fn invoke() {
             sink.write(del_oep5_neovm_receiver(receiver))
         }
         "getOep5NeovmReceivers" => sink.write(get_oep5_neovm_receivers()),
+        "setReceiverCapacity" => {
+            let capacity = source.read().unwrap();
+            sink.write(set_receiver_capacity(capacity));
+        }
+        "getReceiverCapacity" => {
+            sink.write(get_receiver_capacity());
+        }
+        "setOep5ReceiverCapacity" => {
+            let (oep5, capacity) = source.read().unwrap();
+            sink.write(set_oep5_receiver_capacity(oep5, capacity));
+        }
+        "getOep5ReceiverCapacity" => {
+            let oep5 = source.read().unwrap();
+            sink.write(get_oep5_receiver_capacity(oep5));
+        }
+        "getReceiverFillLevels" => {
+            let token_pair_name = source.read().unwrap();
+            sink.write(get_receiver_fill_levels(token_pair_name));
+        }
+        "setContractVmType" => {
+            let (contract, is_neovm) = source.read().unwrap();
+            sink.write(set_contract_vm_type(contract, is_neovm));
+        }
+        "isNeovmContract" => {
+            let contract = source.read().unwrap();
+            sink.write(is_neovm_contract(contract));
+        }
         "registerOep5Erc721Pair" => {
-            let (token_pair_name, oep5_addr, erc721_addr, is_neovm) = source.read().unwrap();
+            let (token_pair_name, oep5_addr, erc721_addr) = source.read().unwrap();
+            //the vm type may be left out when setContractVmType recorded it
+            let is_neovm = source.read().ok();
             sink.write(register_oep5_erc721_pair(
                 token_pair_name,
                 oep5_addr,
@@ -73,15 +139,55 @@ pub fn invoke() {
         }
         "registerOep8Erc1155Pair" => {
             let (token_pair_name, oep8_addr, erc1155_addr) = source.read().unwrap();
+            //callers from before neovm oep8 support pass no vm type, their oep8 is wasm
+            let is_neovm = source.read().unwrap_or(false);
             sink.write(register_oep8_erc1155_pair(
                 token_pair_name,
                 oep8_addr,
                 erc1155_addr,
+                is_neovm,
             ))
         }
-        "transferTokenPairOwner" => {
+        "registerOep4Erc20Pair" => {
+            let (token_pair_name, oep4_addr, erc20_addr, is_neovm) = source.read().unwrap();
+            sink.write(register_oep4_erc20_pair(
+                token_pair_name,
+                oep4_addr,
+                erc20_addr,
+                is_neovm,
+            ))
+        }
+        "unregisterTokenPair" => {
+            let token_pair_name = source.read().unwrap();
+            sink.write(unregister_token_pair(token_pair_name))
+        }
+        "updateTokenPairErc" => {
+            let (token_pair_name, new_erc) = source.read().unwrap();
+            sink.write(update_token_pair_erc(token_pair_name, new_erc))
+        }
+        "setPendingPairOwner" => {
             let (token_pair_name, new_owner) = source.read().unwrap();
-            sink.write(transfer_token_pair_owner(token_pair_name, new_owner))
+            sink.write(set_pending_pair_owner(token_pair_name, new_owner))
+        }
+        "getPendingPairOwner" => {
+            let token_pair_name = source.read().unwrap();
+            sink.write(get_pending_pair_owner(token_pair_name));
+        }
+        "acceptPairOwner" => {
+            let token_pair_name = source.read().unwrap();
+            sink.write(accept_pair_owner(token_pair_name))
+        }
+        "renounceTokenPairOwner" => {
+            let token_pair_name = source.read().unwrap();
+            sink.write(renounce_token_pair_owner(token_pair_name))
+        }
+        "setTokenPairLimit" => {
+            let (token_pair_name, min_amount, max_amount) = source.read().unwrap();
+            sink.write(set_token_pair_limit(token_pair_name, min_amount, max_amount))
+        }
+        "getTokenPairLimit" => {
+            let token_pair_name = source.read().unwrap();
+            sink.write(get_token_pair_limit(token_pair_name));
         }
         "getAllTokenPairName" => {
             sink.write(get_all_token_pair_name());
@@ -91,46 +197,194 @@ pub fn invoke() {
             sink.write(get_token_pair(token_pair_name));
         }
         "oep5ToOrc721" => {
-            let (ont_acct, eth_acct, token_id, token_pair_name) = source.read().unwrap();
+            let (ont_acct, eth_acct, token_id, token_pair_name): (_, _, U128, _) =
+                source.read().unwrap();
             sink.write(oep5_to_erc721(
                 ont_acct,
                 eth_acct,
-                token_id,
+                U256::from(token_id),
+                token_pair_name,
+            ));
+        }
+        //the U256 bridging actions take token ids as 32 bytes big endian and log them the same way
+        "oep5ToOrc721U256" => {
+            let (ont_acct, eth_acct, token_id, token_pair_name) = source.read().unwrap();
+            sink.write(oep5_to_erc721_u256(
+                ont_acct,
+                eth_acct,
+                u256_from_be_slice(token_id),
+                token_pair_name,
+            ));
+        }
+        "oep5ToOrc721Batch" => {
+            let (ont_acct, eth_accts, token_ids, token_pair_name): (
+                &Address,
+                Vec<Address>,
+                Vec<U128>,
+                &[u8],
+            ) = source.read().unwrap();
+            let token_ids: Vec<U256> = token_ids.into_iter().map(U256::from).collect();
+            sink.write(oep5_to_erc721_batch(
+                ont_acct,
+                eth_accts.as_slice(),
+                token_ids.as_slice(),
+                token_pair_name,
+            ));
+        }
+        "oep5ToOrc721BatchU256" => {
+            let (ont_acct, eth_accts, token_ids, token_pair_name): (
+                &Address,
+                Vec<Address>,
+                Vec<&[u8]>,
+                &[u8],
+            ) = source.read().unwrap();
+            let token_ids: Vec<U256> = token_ids.into_iter().map(u256_from_be_slice).collect();
+            sink.write(oep5_to_erc721_batch_u256(
+                ont_acct,
+                eth_accts.as_slice(),
+                token_ids.as_slice(),
+                token_pair_name,
+            ));
+        }
+        "orc721ToOep5" => {
+            let (eth_acct, ont_acct, token_id, token_pair_name): (_, _, U128, _) =
+                source.read().unwrap();
+            sink.write(erc721_to_oep5(
+                eth_acct,
+                ont_acct,
+                U256::from(token_id),
+                token_pair_name,
+            ));
+        }
+        "orc721ToOep5U256" => {
+            let (eth_acct, ont_acct, token_id, token_pair_name) = source.read().unwrap();
+            sink.write(erc721_to_oep5_u256(
+                eth_acct,
+                ont_acct,
+                u256_from_be_slice(token_id),
                 token_pair_name,
             ));
         }
         "mintErc721" => {
-            let (erc721, eth_acct, token_id) = source.read().unwrap();
-            sink.write(mint_erc721(&address(), erc721, eth_acct, token_id));
+            let (erc721, eth_acct, token_id): (_, _, U128) = source.read().unwrap();
+            sink.write(mint_erc721(&address(), erc721, eth_acct, U256::from(token_id)));
         }
         "balanceOfOep5" => {
-            let (ont_acct, eth_acct, is_neovm) = source.read().unwrap();
-            sink.write(balance_of_oep5(ont_acct, eth_acct, is_neovm));
+            //a trailing vm type from older callers is ignored, the recorded one is used
+            let (ont_acct, eth_acct) = source.read().unwrap();
+            sink.write(balance_of_oep5(ont_acct, eth_acct));
         }
         "balanceOfErc721" => {
             let (caller, target, user) = source.read().unwrap();
-            sink.write(balance_of_erc721(caller, target, user));
+            sink.write(to_u128(balance_of_erc721(caller, target, user)));
         }
         "balanceOfErc1155" => {
+            let (caller, target, user, token_id): (_, _, _, U128) = source.read().unwrap();
+            let balance = balance_of_erc1155(caller, target, user, U256::from(token_id));
+            sink.write(to_u128(balance));
+        }
+        //the U256 variants take and return 32 bytes big endian, erc values may exceed 128 bits
+        "balanceOfErc721U256" => {
+            let (caller, target, user) = source.read().unwrap();
+            sink.write(&balance_of_erc721(caller, target, user).to_be_bytes());
+        }
+        "balanceOfErc1155U256" => {
             let (caller, target, user, token_id) = source.read().unwrap();
-            sink.write(balance_of_erc1155(caller, target, user, token_id));
+            let token_id = u256_from_be_slice(token_id);
+            sink.write(&balance_of_erc1155(caller, target, user, token_id).to_be_bytes());
         }
         "mintErc1155" => {
-            let (caller, target, user, token_id, amount) = source.read().unwrap();
-            sink.write(mint_erc1155(caller, target, user, token_id, amount));
+            let (caller, target, user, token_id, amount): (_, _, _, U128, U128) =
+                source.read().unwrap();
+            let token_id = U256::from(token_id);
+            sink.write(mint_erc1155(caller, target, user, token_id, U256::from(amount)));
         }
         "oep8ToOrc1155" => {
-            let (ont_acct, eth_acct, token_id, amount, token_pair_name) = source.read().unwrap();
+            let (ont_acct, eth_acct, token_id, amount, token_pair_name): (_, _, U128, _, _) =
+                source.read().unwrap();
             sink.write(oep8_to_erc1155(
                 ont_acct,
                 eth_acct,
-                token_id,
+                U256::from(token_id),
+                token_pair_name,
+                amount,
+            ));
+        }
+        "oep8ToOrc1155U256" => {
+            let (ont_acct, eth_acct, token_id, amount, token_pair_name) = source.read().unwrap();
+            sink.write(oep8_to_erc1155_u256(
+                ont_acct,
+                eth_acct,
+                u256_from_be_slice(token_id),
                 token_pair_name,
                 amount,
             ));
         }
+        "oep8ToOrc1155Batch" => {
+            let (ont_acct, eth_acct, token_ids, amounts, token_pair_name): (
+                &Address,
+                &Address,
+                Vec<U128>,
+                Vec<U128>,
+                &[u8],
+            ) = source.read().unwrap();
+            let token_ids: Vec<U256> = token_ids.into_iter().map(U256::from).collect();
+            sink.write(oep8_to_erc1155_batch(
+                ont_acct,
+                eth_acct,
+                token_ids.as_slice(),
+                amounts.as_slice(),
+                token_pair_name,
+            ));
+        }
+        "oep8ToOrc1155BatchU256" => {
+            let (ont_acct, eth_acct, token_ids, amounts, token_pair_name): (
+                &Address,
+                &Address,
+                Vec<&[u8]>,
+                Vec<U128>,
+                &[u8],
+            ) = source.read().unwrap();
+            let token_ids: Vec<U256> = token_ids.into_iter().map(u256_from_be_slice).collect();
+            sink.write(oep8_to_erc1155_batch_u256(
+                ont_acct,
+                eth_acct,
+                token_ids.as_slice(),
+                amounts.as_slice(),
+                token_pair_name,
+            ));
+        }
+        "orc1155ToOep8" => {
+            let (eth_acct, ont_acct, token_id, amount, token_pair_name): (_, _, U128, _, _) =
+                source.read().unwrap();
+            sink.write(erc1155_to_oep8(
+                eth_acct,
+                ont_acct,
+                U256::from(token_id),
+                token_pair_name,
+                amount,
+            ));
+        }
+        "orc1155ToOep8U256" => {
+            let (eth_acct, ont_acct, token_id, amount, token_pair_name) = source.read().unwrap();
+            sink.write(erc1155_to_oep8_u256(
+                eth_acct,
+                ont_acct,
+                u256_from_be_slice(token_id),
+                token_pair_name,
+                amount,
+            ));
+        }
+        "oep4ToOrc20" => {
+            let (ont_acct, eth_acct, amount, token_pair_name) = source.read().unwrap();
+            sink.write(oep4_to_erc20(ont_acct, eth_acct, amount, token_pair_name));
+        }
+        "orc20ToOep4" => {
+            let (eth_acct, ont_acct, amount, token_pair_name) = source.read().unwrap();
+            sink.write(erc20_to_oep4(eth_acct, ont_acct, amount, token_pair_name));
+        }
         _ => panic!("unsupported action2!"),
     }
 
-    ret(sink.bytes())
+    sink.bytes().to_vec()
 }
@@ -0,0 +1,928 @@
+use crate::bridge::*;
+use common::ethabi::u256_from_be_slice;
+use common::mock::*;
+use common::oep5and8::{put_contract_vm_type, ONT_CONTRACT_ADDRESS};
+use core::cell::RefCell;
+use ostd::abi::{Encoder, Sink, Source};
+use ostd::mock::RuntimeHandle;
+use ostd::prelude::*;
+use ostd::types::U256;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+
+fn admin() -> Address {
+    Address::repeat_byte(1)
+}
+
+fn user() -> Address {
+    Address::repeat_byte(2)
+}
+
+fn eth_user() -> Address {
+    Address::repeat_byte(3)
+}
+
+fn bridge() -> Address {
+    Address::repeat_byte(10)
+}
+
+fn receiver() -> Address {
+    Address::repeat_byte(11)
+}
+
+fn oep5() -> Address {
+    Address::repeat_byte(20)
+}
+
+fn oep8() -> Address {
+    Address::repeat_byte(21)
+}
+
+fn erc721() -> Address {
+    Address::repeat_byte(22)
+}
+
+fn erc1155() -> Address {
+    Address::repeat_byte(23)
+}
+
+fn erc20() -> Address {
+    Address::repeat_byte(24)
+}
+
+fn id(n: u8) -> U256 {
+    u256_from_be_slice(&[n])
+}
+
+struct Env {
+    world: World,
+    handle: Rc<RuntimeHandle>,
+    oep5: Rc<RefCell<FakeOep5>>,
+    oep8: Rc<RefCell<FakeOep8>>,
+    erc721: Rc<RefCell<FakeErc721>>,
+    erc1155: Rc<RefCell<FakeErc1155>>,
+}
+
+//bridge with a receiver, an oep5/erc721 pair named "nft" and an oep8/erc1155 pair named "multi"
+fn setup() -> Env {
+    let world = World::new();
+    let fake_oep5 = world.deploy(&oep5(), FakeOep5::default());
+    let fake_oep8 = world.deploy(&oep8(), FakeOep8::default());
+    let fake_erc721 = world.deploy(&erc721(), FakeErc721::default());
+    let fake_erc1155 = world.deploy(&erc1155(), FakeErc1155::default());
+    world.deploy(&receiver(), FakeReceiver::new(&world, &receiver()));
+    let handle = world.install();
+    handle.address(&bridge()).witness(&[admin()]);
+    assert!(initialize(&admin()));
+    add_oep5_neovm_receiver(&[receiver()]);
+    assert!(register_oep5_erc721_pair(
+        "nft",
+        &oep5(),
+        &erc721(),
+        Some(false)
+    ));
+    assert!(register_oep8_erc1155_pair(
+        "multi",
+        &oep8(),
+        &erc1155(),
+        false
+    ));
+    Env {
+        world,
+        handle,
+        oep5: fake_oep5,
+        oep8: fake_oep8,
+        erc721: fake_erc721,
+        erc1155: fake_erc1155,
+    }
+}
+
+#[test]
+fn test_admin_handover() {
+    let env = setup();
+    let new_admin = Address::repeat_byte(4);
+    assert!(set_pending_admin(&new_admin));
+    assert_eq!(get_pending_admin(), new_admin);
+    env.handle.witness(&[new_admin]);
+    assert!(accept_admin());
+    assert_eq!(get_admin(), new_admin);
+    assert_eq!(get_pending_admin(), Address::default());
+}
+
+#[test]
+#[should_panic(expected = "has inited")]
+fn test_initialize_twice() {
+    let _env = setup();
+    initialize(&admin());
+}
+
+#[test]
+fn test_register_token_pairs() {
+    let _env = setup();
+    assert_eq!(
+        get_all_token_pair_name(),
+        vec![String::from("nft"), String::from("multi")]
+    );
+}
+
+#[test]
+#[should_panic(expected = "erc contract does not support erc721")]
+fn test_register_rejects_wrong_erc_interface() {
+    let _env = setup();
+    register_oep5_erc721_pair("nft2", &oep5(), &erc1155(), Some(false));
+}
+
+#[test]
+fn test_register_records_vm_type() {
+    let _env = setup();
+    assert!(!is_neovm_contract(&oep5()));
+    assert!(!is_neovm_contract(&oep8()));
+}
+
+#[test]
+#[should_panic(expected = "contract vm type does not match the recorded one")]
+fn test_register_conflicting_vm_type() {
+    let _env = setup();
+    register_oep5_erc721_pair("nft2", &oep5(), &erc721(), Some(true));
+}
+
+#[test]
+fn test_register_with_recorded_vm_type() {
+    let env = setup();
+    let (oep5_2, erc721_2) = (Address::repeat_byte(28), Address::repeat_byte(29));
+    env.world.deploy(&oep5_2, FakeOep5::default());
+    env.world.deploy(&erc721_2, FakeErc721::default());
+    assert!(set_contract_vm_type(&oep5_2, false));
+    assert!(register_oep5_erc721_pair("nft2", &oep5_2, &erc721_2, None));
+    assert!(!is_neovm_contract(&oep5_2));
+}
+
+#[test]
+#[should_panic(expected = "unknown contract vm type")]
+fn test_register_without_vm_type() {
+    let env = setup();
+    let (oep5_2, erc721_2) = (Address::repeat_byte(28), Address::repeat_byte(29));
+    env.world.deploy(&oep5_2, FakeOep5::default());
+    env.world.deploy(&erc721_2, FakeErc721::default());
+    register_oep5_erc721_pair("nft2", &oep5_2, &erc721_2, None);
+}
+
+//a wrong record no pair relies on, e.g. written before the probe existed, can be corrected
+#[test]
+fn test_correct_unused_vm_type() {
+    let env = setup();
+    let oep5_2 = Address::repeat_byte(28);
+    env.world.deploy(&oep5_2, FakeOep5::default());
+    put_contract_vm_type(&oep5_2, true);
+    assert!(set_contract_vm_type(&oep5_2, false));
+    assert!(!is_neovm_contract(&oep5_2));
+}
+
+#[test]
+#[should_panic(expected = "contract vm type is used by a token pair")]
+fn test_set_vm_type_of_registered_contract() {
+    let _env = setup();
+    set_contract_vm_type(&oep5(), false);
+}
+
+#[test]
+#[should_panic(expected = "token pair name has registered")]
+fn test_register_duplicated_name() {
+    let _env = setup();
+    register_oep8_erc1155_pair("nft", &oep8(), &erc1155(), false);
+}
+
+#[test]
+#[should_panic(expected = "pending owner is zero address")]
+fn test_unregister_clears_pending_owner() {
+    let env = setup();
+    let owner = Address::repeat_byte(5);
+    assert!(set_pending_pair_owner(b"nft", &owner));
+    assert!(unregister_token_pair(b"nft"));
+    assert!(register_oep5_erc721_pair(
+        "nft",
+        &oep5(),
+        &erc721(),
+        Some(false)
+    ));
+    assert_eq!(get_pending_pair_owner(b"nft"), Address::default());
+    env.handle.witness(&[owner]);
+    accept_pair_owner(b"nft");
+}
+
+#[test]
+fn test_unregister_pair_sharing_oep8() {
+    let env = setup();
+    let erc1155_2 = Address::repeat_byte(25);
+    env.world.deploy(&erc1155_2, FakeErc1155::default());
+    assert!(register_oep8_erc1155_pair(
+        "multi2",
+        &oep8(),
+        &erc1155_2,
+        false
+    ));
+    env.oep8.borrow_mut().mint(&user(), 1, 100);
+    env.handle.witness(&[user()]);
+    assert!(oep8_to_erc1155(
+        &user(),
+        &eth_user(),
+        id(1),
+        b"multi",
+        U128::new(40)
+    ));
+
+    //the escrow of "multi" does not block the other pair of the same oep8 contract
+    env.handle.witness(&[admin()]);
+    let res = catch_unwind(AssertUnwindSafe(|| unregister_token_pair(b"multi")));
+    assert!(res.is_err());
+    assert!(unregister_token_pair(b"multi2"));
+
+    env.handle.witness(&[eth_user()]);
+    assert!(erc1155_to_oep8(
+        &eth_user(),
+        &user(),
+        id(1),
+        b"multi",
+        U128::new(40)
+    ));
+    env.handle.witness(&[admin()]);
+    assert!(unregister_token_pair(b"multi"));
+}
+
+#[test]
+fn test_pair_owner_pause() {
+    let env = setup();
+    let owner = Address::repeat_byte(5);
+    assert!(set_pending_pair_owner(b"nft", &owner));
+    env.handle.witness(&[owner]);
+    assert!(accept_pair_owner(b"nft"));
+    assert!(pause_token_pair(b"nft"));
+    assert!(unpause_token_pair(b"nft"));
+    assert!(!is_token_pair_paused(b"nft"));
+
+    assert!(pause_token_pair(b"nft"));
+    env.handle.witness(&[admin()]);
+    assert!(pause_token_pair(b"nft"));
+    assert!(unpause_token_pair(b"nft"));
+    assert!(!is_token_pair_paused(b"nft"));
+}
+
+#[test]
+#[should_panic(expected = "paused by admin")]
+fn test_pair_owner_unpause_admin_pause() {
+    let env = setup();
+    let owner = Address::repeat_byte(5);
+    assert!(set_pending_pair_owner(b"nft", &owner));
+    assert!(pause_token_pair(b"nft"));
+    //the owner can not lift a pause of the admin
+    env.handle.witness(&[owner]);
+    assert!(accept_pair_owner(b"nft"));
+    unpause_token_pair(b"nft");
+}
+
+#[test]
+#[should_panic(expected = "amount is less than min amount")]
+fn test_oep5_to_erc721_limit() {
+    let env = setup();
+    assert!(set_token_pair_limit(b"nft", U128::new(2), U128::new(0)));
+    env.oep5.borrow_mut().mint(&user(), 1);
+    env.handle.witness(&[user()]);
+    oep5_to_erc721(&user(), &eth_user(), id(1), b"nft");
+}
+
+#[test]
+fn test_oep5_erc721_round_trip() {
+    let env = setup();
+    env.oep5.borrow_mut().mint(&user(), 1);
+    env.handle.witness(&[user()]);
+    assert!(oep5_to_erc721(&user(), &eth_user(), id(1), b"nft"));
+    assert_eq!(env.oep5.borrow().owner_of(1), receiver());
+    assert_eq!(env.erc721.borrow().owner_of(id(1)), eth_user());
+
+    env.handle.witness(&[eth_user()]);
+    assert!(erc721_to_oep5(&eth_user(), &user(), id(1), b"nft"));
+    assert_eq!(env.oep5.borrow().owner_of(1), user());
+    assert_eq!(env.erc721.borrow().balance_of(&eth_user()), 0);
+}
+
+//the erc721 mint returns the minted id, id 0 must not read as a failed mint
+#[test]
+fn test_oep5_to_erc721_token_id_zero() {
+    let env = setup();
+    env.oep5.borrow_mut().mint(&user(), 0);
+    env.handle.witness(&[user()]);
+    assert!(oep5_to_erc721(&user(), &eth_user(), id(0), b"nft"));
+    assert_eq!(env.erc721.borrow().owner_of(id(0)), eth_user());
+}
+
+fn call_dispatch<T: Encoder>(param: T) -> bool {
+    let mut sink = Sink::new(16);
+    sink.write(param);
+    let res = crate::dispatch(sink.bytes());
+    Source::new(&res).read().unwrap()
+}
+
+#[test]
+fn test_oep5_erc721_u256_actions() {
+    let env = setup();
+    env.oep5.borrow_mut().mint(&user(), 7);
+    let token_id = id(7).to_be_bytes();
+    let nft: &[u8] = b"nft";
+    env.handle.witness(&[user()]);
+    let param = ("oep5ToOrc721U256", &user(), &eth_user(), &token_id[..], nft);
+    assert!(call_dispatch(param));
+    assert_eq!(env.erc721.borrow().owner_of(id(7)), eth_user());
+
+    env.handle.witness(&[eth_user()]);
+    let param = ("orc721ToOep5U256", &eth_user(), &user(), &token_id[..], nft);
+    assert!(call_dispatch(param));
+    assert_eq!(env.oep5.borrow().owner_of(7), user());
+}
+
+#[test]
+fn test_oep8_erc1155_u256_actions() {
+    let env = setup();
+    env.oep8.borrow_mut().mint(&user(), 1, 100);
+    env.oep8.borrow_mut().mint(&user(), 2, 100);
+    let (id1, id2) = (id(1).to_be_bytes(), id(2).to_be_bytes());
+    let multi: &[u8] = b"multi";
+    env.handle.witness(&[user()]);
+    let amount = U128::new(30);
+    let param = (
+        "oep8ToOrc1155U256",
+        &user(),
+        &eth_user(),
+        &id1[..],
+        amount,
+        multi,
+    );
+    assert!(call_dispatch(param));
+    let ids: Vec<&[u8]> = vec![&id1[..], &id2[..]];
+    let amounts = vec![U128::new(10), U128::new(20)];
+    let param = (
+        "oep8ToOrc1155BatchU256",
+        &user(),
+        &eth_user(),
+        ids,
+        amounts,
+        multi,
+    );
+    assert!(call_dispatch(param));
+    assert_eq!(env.erc1155.borrow().balance_of(&eth_user(), id(1)), 40);
+    assert_eq!(env.erc1155.borrow().balance_of(&eth_user(), id(2)), 20);
+
+    env.handle.witness(&[eth_user()]);
+    let param = (
+        "orc1155ToOep8U256",
+        &eth_user(),
+        &user(),
+        &id2[..],
+        U128::new(20),
+        multi,
+    );
+    assert!(call_dispatch(param));
+    assert_eq!(env.oep8.borrow().balance_of(&user(), 2), 100);
+}
+
+#[test]
+#[should_panic(expected = "token id is out of oep range")]
+fn test_u256_token_id_out_of_oep_range() {
+    let env = setup();
+    env.handle.witness(&[user()]);
+    let mut token_id = [0u8; 17];
+    token_id[0] = 1;
+    oep5_to_erc721_u256(&user(), &eth_user(), u256_from_be_slice(&token_id), b"nft");
+}
+
+#[test]
+#[should_panic(expected = "oep5 token is not locked by a bridge receiver")]
+fn test_erc721_to_oep5_from_removed_receiver() {
+    let env = setup();
+    env.oep5.borrow_mut().mint(&user(), 1);
+    env.handle.witness(&[user()]);
+    assert!(oep5_to_erc721(&user(), &eth_user(), id(1), b"nft"));
+    env.handle.witness(&[admin()]);
+    del_oep5_neovm_receiver(&receiver());
+    env.handle.witness(&[eth_user()]);
+    erc721_to_oep5(&eth_user(), &user(), id(1), b"nft");
+}
+
+#[test]
+fn test_oep8_erc1155_round_trip() {
+    let env = setup();
+    env.oep8.borrow_mut().mint(&user(), 1, 100);
+    env.oep8.borrow_mut().mint(&user(), 2, 100);
+    env.handle.witness(&[user()]);
+    assert!(oep8_to_erc1155(
+        &user(),
+        &eth_user(),
+        id(1),
+        b"multi",
+        U128::new(40)
+    ));
+    assert!(oep8_to_erc1155_batch(
+        &user(),
+        &eth_user(),
+        &[id(1), id(2)],
+        &[U128::new(10), U128::new(20)],
+        b"multi"
+    ));
+    assert_eq!(env.oep8.borrow().balance_of(&bridge(), 1), 50);
+    assert_eq!(env.oep8.borrow().balance_of(&bridge(), 2), 20);
+    assert_eq!(env.erc1155.borrow().balance_of(&eth_user(), id(1)), 50);
+
+    env.handle.witness(&[eth_user()]);
+    assert!(erc1155_to_oep8(
+        &eth_user(),
+        &user(),
+        id(1),
+        b"multi",
+        U128::new(50)
+    ));
+    assert_eq!(env.oep8.borrow().balance_of(&user(), 1), 100);
+    assert_eq!(env.erc1155.borrow().balance_of(&eth_user(), id(1)), 0);
+}
+
+#[test]
+fn test_migrate_moves_oep8_escrow() {
+    let env = setup();
+    env.oep8.borrow_mut().mint(&user(), 1, 100);
+    env.handle.witness(&[user()]);
+    assert!(oep8_to_erc1155(
+        &user(),
+        &eth_user(),
+        id(1),
+        b"multi",
+        U128::new(60)
+    ));
+    env.handle.witness(&[admin()]);
+    assert!(migrate(
+        b"code",
+        3,
+        "bridge",
+        "2",
+        "ont",
+        "ont@ont.io",
+        "nft bridge"
+    ));
+    let new_bridge = migrated_address(b"code");
+    assert_ne!(new_bridge, bridge());
+    let oep8 = env.oep8.borrow();
+    assert_eq!(oep8.balance_of(&bridge(), 1), 0);
+    assert_eq!(oep8.balance_of(&new_bridge, 1), 60);
+}
+
+//a neovm oep8 pair, every oep8 call goes through the neovm encoding
+#[test]
+fn test_neovm_oep8_round_trip() {
+    let env = setup();
+    let (neo_oep8, neo_erc1155) = (Address::repeat_byte(26), Address::repeat_byte(27));
+    let fake_oep8 = env.world.deploy(
+        &neo_oep8,
+        FakeOep8 {
+            is_neovm: true,
+            ..FakeOep8::default()
+        },
+    );
+    let fake_erc1155 = env.world.deploy(&neo_erc1155, FakeErc1155::default());
+    assert!(register_oep8_erc1155_pair(
+        "neo",
+        &neo_oep8,
+        &neo_erc1155,
+        true
+    ));
+    assert!(is_neovm_contract(&neo_oep8));
+    fake_oep8.borrow_mut().mint(&user(), 1, 100);
+    fake_oep8.borrow_mut().mint(&user(), 2, 100);
+
+    env.handle.witness(&[user()]);
+    assert!(oep8_to_erc1155(
+        &user(),
+        &eth_user(),
+        id(1),
+        b"neo",
+        U128::new(40)
+    ));
+    assert!(oep8_to_erc1155_batch(
+        &user(),
+        &eth_user(),
+        &[id(1), id(2)],
+        &[U128::new(10), U128::new(20)],
+        b"neo"
+    ));
+    assert_eq!(fake_oep8.borrow().balance_of(&bridge(), 1), 50);
+    assert_eq!(fake_oep8.borrow().balance_of(&bridge(), 2), 20);
+    assert_eq!(fake_erc1155.borrow().balance_of(&eth_user(), id(1)), 50);
+
+    env.handle.witness(&[eth_user()]);
+    assert!(erc1155_to_oep8(
+        &eth_user(),
+        &user(),
+        id(1),
+        b"neo",
+        U128::new(50)
+    ));
+    assert_eq!(fake_oep8.borrow().balance_of(&user(), 1), 100);
+
+    env.handle.witness(&[admin()]);
+    assert!(migrate(
+        b"code",
+        3,
+        "bridge",
+        "2",
+        "ont",
+        "ont@ont.io",
+        "nft bridge"
+    ));
+    let new_bridge = migrated_address(b"code");
+    assert_eq!(fake_oep8.borrow().balance_of(&bridge(), 2), 0);
+    assert_eq!(fake_oep8.borrow().balance_of(&new_bridge, 2), 20);
+}
+
+#[test]
+#[should_panic(expected = "mint failed")]
+fn test_erc721_mint_without_effect() {
+    let env = setup();
+    env.erc721.borrow_mut().failure = EvmFailure::MintNoop;
+    env.oep5.borrow_mut().mint(&user(), 1);
+    env.handle.witness(&[user()]);
+    oep5_to_erc721(&user(), &eth_user(), id(1), b"nft");
+}
+
+#[test]
+#[should_panic(expected = "mint failed, reverted: mint reverted")]
+fn test_erc721_mint_revert_reason() {
+    let env = setup();
+    env.erc721.borrow_mut().failure = EvmFailure::MintRevert;
+    env.oep5.borrow_mut().mint(&user(), 1);
+    env.handle.witness(&[user()]);
+    oep5_to_erc721(&user(), &eth_user(), id(1), b"nft");
+}
+
+#[test]
+#[should_panic(expected = "balanceOf failed, empty return data")]
+fn test_erc1155_empty_balance() {
+    let env = setup();
+    env.erc1155.borrow_mut().failure = EvmFailure::EmptyBalance;
+    env.oep8.borrow_mut().mint(&user(), 1, 100);
+    env.handle.witness(&[user()]);
+    oep8_to_erc1155(&user(), &eth_user(), id(1), b"multi", U128::new(10));
+}
+
+#[test]
+#[should_panic(expected = "mint failed")]
+fn test_erc1155_batch_mint_without_effect() {
+    let env = setup();
+    env.erc1155.borrow_mut().failure = EvmFailure::MintNoop;
+    env.oep8.borrow_mut().mint(&user(), 1, 100);
+    env.handle.witness(&[user()]);
+    oep8_to_erc1155_batch(&user(), &eth_user(), &[id(1)], &[U128::new(10)], b"multi");
+}
+
+//native ont paired with an erc20 named "ont", user holds 1000 ont
+fn setup_ont_pair(env: &Env) -> (Rc<RefCell<FakeOnt>>, Rc<RefCell<FakeErc20>>) {
+    let ont = env.world.deploy(&ONT_CONTRACT_ADDRESS, FakeOnt::default());
+    let erc20 = env.world.deploy(&erc20(), FakeErc20::default());
+    ont.borrow_mut().mint(&user(), 1000);
+    env.handle.witness(&[admin()]);
+    assert!(register_oep4_erc20_pair(
+        "ont",
+        &ONT_CONTRACT_ADDRESS,
+        &erc20(),
+        false
+    ));
+    (ont, erc20)
+}
+
+#[test]
+fn test_ont_erc20_round_trip() {
+    let env = setup();
+    let (ont, erc20) = setup_ont_pair(&env);
+    env.handle.witness(&[user()]);
+    assert!(oep4_to_erc20(&user(), &eth_user(), U128::new(300), b"ont"));
+    assert_eq!(ont.borrow().balance_of(&user()), 700);
+    assert_eq!(ont.borrow().balance_of(&bridge()), 300);
+    assert_eq!(erc20.borrow().balance_of(&eth_user()), 300);
+
+    env.handle.witness(&[eth_user()]);
+    assert!(erc20_to_oep4(&eth_user(), &user(), U128::new(100), b"ont"));
+    assert_eq!(ont.borrow().balance_of(&user()), 800);
+    assert_eq!(ont.borrow().balance_of(&bridge()), 200);
+    assert_eq!(erc20.borrow().total_supply(), 200);
+}
+
+#[test]
+#[should_panic(expected = "mint failed")]
+fn test_erc20_mint_without_effect() {
+    let env = setup();
+    let (_ont, erc20) = setup_ont_pair(&env);
+    erc20.borrow_mut().failure = EvmFailure::MintNoop;
+    env.handle.witness(&[user()]);
+    oep4_to_erc20(&user(), &eth_user(), U128::new(300), b"ont");
+}
+
+#[test]
+#[should_panic(expected = "insufficient erc20 balance")]
+fn test_erc20_to_oep4_without_balance() {
+    let env = setup();
+    let _pair = setup_ont_pair(&env);
+    env.handle.witness(&[eth_user()]);
+    erc20_to_oep4(&eth_user(), &user(), U128::new(100), b"ont");
+}
+
+//pairs are (name, oep, erc) and registered by the operations, receivers are added by them
+const OEP5_PAIRS: [(&str, u8, u8); 2] = [("nft0", 40, 41), ("nft1", 42, 43)];
+const OEP8_PAIRS: [(&str, u8, u8); 2] = [("multi0", 44, 45), ("multi1", 46, 47)];
+const RECEIVERS: [u8; 3] = [30, 31, 32];
+const OEP8_IDS: [u8; 3] = [1, 2, 3];
+
+#[derive(Clone, Debug)]
+enum Op {
+    Register(usize),
+    AddReceiver(usize),
+    DelReceiver(usize),
+    Oep5ToErc721(usize, u8),
+    Erc721ToOep5(usize, u8),
+    Oep8ToErc1155(usize, u8, u128),
+    Oep8ToErc1155Batch(usize, u128, u128),
+    Erc1155ToOep8(usize, u8, u128),
+    Migrate,
+}
+
+//xorshift64, every case is replayed from its seed
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    //lo..=hi, the ranges are small enough for the modulo bias not to matter
+    fn range(&mut self, lo: u64, hi: u64) -> u64 {
+        lo + self.next_u64() % (hi - lo + 1)
+    }
+}
+
+//weighted towards bridging, a migrate every 22 ops on average
+fn random_op(rng: &mut Rng) -> Op {
+    let pair = rng.range(0, 1) as usize;
+    match rng.range(0, 21) {
+        0..=1 => Op::Register(rng.range(0, 3) as usize),
+        2..=3 => Op::AddReceiver(rng.range(0, 2) as usize),
+        4 => Op::DelReceiver(rng.range(0, 2) as usize),
+        5..=8 => Op::Oep5ToErc721(pair, rng.range(1, 6) as u8),
+        9..=11 => Op::Erc721ToOep5(pair, rng.range(1, 6) as u8),
+        12..=15 => Op::Oep8ToErc1155(pair, rng.range(1, 3) as u8, rng.range(1, 60) as u128),
+        16..=17 => Op::Oep8ToErc1155Batch(pair, rng.range(1, 40) as u128, rng.range(1, 40) as u128),
+        18..=20 => Op::Erc1155ToOep8(pair, rng.range(1, 3) as u8, rng.range(1, 60) as u128),
+        _ => Op::Migrate,
+    }
+}
+
+fn random_ops(seed: u64) -> Vec<Op> {
+    let mut rng = Rng::new(seed);
+    let len = rng.range(1, 39);
+    (0..len).map(|_| random_op(&mut rng)).collect()
+}
+
+struct Escrow {
+    world: World,
+    handle: Rc<RuntimeHandle>,
+    oep5: Vec<Rc<RefCell<FakeOep5>>>,
+    erc721: Vec<Rc<RefCell<FakeErc721>>>,
+    oep8: Vec<Rc<RefCell<FakeOep8>>>,
+    erc1155: Vec<Rc<RefCell<FakeErc1155>>>,
+    //oep5 pairs first, then oep8 pairs
+    registered: [bool; 4],
+    //the running bridge, the contract migrated to after a migrate
+    this: Address,
+    migrations: u8,
+}
+
+//bridge with no pairs and no receivers added, user holds oep5 ids 1..=6 and 100 of each oep8 id
+fn setup_escrow() -> Escrow {
+    let world = World::new();
+    let mut env = Escrow {
+        world: world.clone(),
+        handle: world.install(),
+        oep5: Vec::new(),
+        erc721: Vec::new(),
+        oep8: Vec::new(),
+        erc1155: Vec::new(),
+        registered: [false; 4],
+        this: bridge(),
+        migrations: 0,
+    };
+    for (_, oep, erc) in OEP5_PAIRS.iter() {
+        let oep5 = world.deploy(&Address::repeat_byte(*oep), FakeOep5::default());
+        for n in 1..=6 {
+            oep5.borrow_mut().mint(&user(), n);
+        }
+        env.oep5.push(oep5);
+        let erc721 = FakeErc721::default();
+        env.erc721
+            .push(world.deploy(&Address::repeat_byte(*erc), erc721));
+    }
+    for (_, oep, erc) in OEP8_PAIRS.iter() {
+        let oep8 = world.deploy(&Address::repeat_byte(*oep), FakeOep8::default());
+        for n in OEP8_IDS.iter() {
+            oep8.borrow_mut().mint(&user(), *n as u128, 100);
+        }
+        env.oep8.push(oep8);
+        let erc1155 = FakeErc1155::default();
+        env.erc1155
+            .push(world.deploy(&Address::repeat_byte(*erc), erc1155));
+    }
+    for n in RECEIVERS.iter() {
+        world.deploy_wasm(&Address::repeat_byte(*n), oep5_receiver::dispatch);
+    }
+    env.handle.address(&bridge()).witness(&[admin()]);
+    assert!(initialize(&admin()));
+    for n in RECEIVERS.iter() {
+        let receiver = Address::repeat_byte(*n);
+        world.call_wasm(&receiver, ("init", &admin()));
+        world.call_wasm(&receiver, ("setNftBridge", &bridge()));
+    }
+    //small enough for the receivers to fill up
+    assert!(set_receiver_capacity(U128::new(3)));
+    env
+}
+
+impl Escrow {
+    //ops the bridge would reject are skipped, an abort is not rolled back by the mock runtime
+    fn apply(&mut self, op: &Op) {
+        let signer = match *op {
+            Op::Oep5ToErc721(..) | Op::Oep8ToErc1155(..) | Op::Oep8ToErc1155Batch(..) => user(),
+            Op::Erc721ToOep5(..) | Op::Erc1155ToOep8(..) => eth_user(),
+            _ => admin(),
+        };
+        //on chain the calling bridge passes the receivers' check_witness, the mock only has signers
+        self.handle.witness(&[signer, self.this]);
+        match *op {
+            Op::Register(k) if !self.registered[k] => {
+                if k < 2 {
+                    let (name, oep, erc) = OEP5_PAIRS[k];
+                    let (oep, erc) = (Address::repeat_byte(oep), Address::repeat_byte(erc));
+                    assert!(register_oep5_erc721_pair(name, &oep, &erc, Some(false)));
+                } else {
+                    let (name, oep, erc) = OEP8_PAIRS[k - 2];
+                    let (oep, erc) = (Address::repeat_byte(oep), Address::repeat_byte(erc));
+                    assert!(register_oep8_erc1155_pair(name, &oep, &erc, false));
+                }
+                self.registered[k] = true;
+            }
+            Op::AddReceiver(r) => {
+                add_oep5_neovm_receiver(&[Address::repeat_byte(RECEIVERS[r])]);
+            }
+            Op::DelReceiver(r) => {
+                let receiver = Address::repeat_byte(RECEIVERS[r]);
+                if get_oep5_neovm_receivers().contains(&receiver) {
+                    del_oep5_neovm_receiver(&receiver);
+                }
+            }
+            Op::Oep5ToErc721(p, n) if self.registered[p] => {
+                if self.oep5[p].borrow().owner_of(n as u128) == user() {
+                    //false when every receiver is full
+                    oep5_to_erc721(&user(), &eth_user(), id(n), OEP5_PAIRS[p].0.as_bytes());
+                }
+            }
+            Op::Erc721ToOep5(p, n) if self.registered[p] => {
+                let receiver = self.oep5[p].borrow().owner_of(n as u128);
+                //tokens left in a removed receiver can not be withdrawn by the bridge
+                let locked = get_oep5_neovm_receivers().contains(&receiver);
+                if locked && self.erc721[p].borrow().owner_of(id(n)) == eth_user() {
+                    let name = OEP5_PAIRS[p].0.as_bytes();
+                    assert!(erc721_to_oep5(&eth_user(), &user(), id(n), name));
+                }
+            }
+            Op::Oep8ToErc1155(p, n, amount) if self.registered[p + 2] => {
+                if self.oep8[p].borrow().balance_of(&user(), n as u128) >= amount {
+                    let name = OEP8_PAIRS[p].0.as_bytes();
+                    let amount = U128::new(amount);
+                    assert!(oep8_to_erc1155(&user(), &eth_user(), id(n), name, amount));
+                }
+            }
+            Op::Oep8ToErc1155Batch(p, a, b) if self.registered[p + 2] => {
+                let oep8 = self.oep8[p].borrow();
+                let enough = oep8.balance_of(&user(), 1) >= a && oep8.balance_of(&user(), 2) >= b;
+                drop(oep8);
+                if enough {
+                    assert!(oep8_to_erc1155_batch(
+                        &user(),
+                        &eth_user(),
+                        &[id(1), id(2)],
+                        &[U128::new(a), U128::new(b)],
+                        OEP8_PAIRS[p].0.as_bytes()
+                    ));
+                }
+            }
+            Op::Erc1155ToOep8(p, n, amount) if self.registered[p + 2] => {
+                if self.erc1155[p].borrow().balance_of(&eth_user(), id(n)) >= amount {
+                    let name = OEP8_PAIRS[p].0.as_bytes();
+                    let amount = U128::new(amount);
+                    assert!(erc1155_to_oep8(&eth_user(), &user(), id(n), name, amount));
+                }
+            }
+            Op::Migrate => {
+                //every migrate deploys another code, so it moves to another address
+                self.migrations += 1;
+                let code = [b'v', self.migrations];
+                assert!(migrate(
+                    &code,
+                    3,
+                    "bridge",
+                    "2",
+                    "ont",
+                    "ont@ont.io",
+                    "nft bridge"
+                ));
+                let new_bridge = migrated_address(&code);
+                //the new contract starts from the migrated storage, keep bridging through it
+                self.handle.address(&new_bridge);
+                self.this = new_bridge;
+                //receivers only lock and withdraw for the bridge they point at
+                for n in RECEIVERS.iter() {
+                    let receiver = Address::repeat_byte(*n);
+                    self.world
+                        .call_wasm(&receiver, ("setNftBridge", &new_bridge));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_invariants(&self) {
+        let receivers: Vec<Address> = RECEIVERS.iter().map(|n| Address::repeat_byte(*n)).collect();
+        //every minted erc721 id is backed by exactly one oep5 id locked in some receiver,
+        //and each receiver lists exactly the ids it holds
+        for (k, (oep5, erc721)) in self.oep5.iter().zip(self.erc721.iter()).enumerate() {
+            let contract = Address::repeat_byte(OEP5_PAIRS[k].1);
+            let mut locked: Vec<[u8; 32]> = Vec::new();
+            for receiver in receivers.iter() {
+                let res = self
+                    .world
+                    .call_wasm(receiver, ("getOep5Ids", &contract))
+                    .unwrap();
+                let ids: Vec<U128> = Source::new(res.as_slice()).read().unwrap();
+                let mut listed: Vec<u128> = ids.iter().map(|n| n.raw()).collect();
+                listed.sort_unstable();
+                let held: Vec<u128> = oep5
+                    .borrow()
+                    .owners
+                    .iter()
+                    .filter(|(_, owner)| *owner == receiver)
+                    .map(|(n, _)| *n)
+                    .collect();
+                assert_eq!(listed, held, "receiver {:?} lists other ids", receiver);
+                locked.extend(ids.iter().map(|n| U256::from(*n).to_be_bytes()));
+            }
+            locked.sort_unstable();
+            let minted: Vec<[u8; 32]> = erc721.borrow().owners.keys().cloned().collect();
+            assert_eq!(minted, locked);
+        }
+        //oep8 escrow covers the erc1155 minted, held by the running bridge
+        for (oep8, erc1155) in self.oep8.iter().zip(self.erc1155.iter()) {
+            let oep8 = oep8.borrow();
+            for n in OEP8_IDS.iter() {
+                let minted: u128 = erc1155
+                    .borrow()
+                    .balances
+                    .iter()
+                    .filter(|((_, token_id), _)| *token_id == id(*n).to_be_bytes())
+                    .map(|(_, &amount)| amount)
+                    .sum();
+                let escrow = oep8.balance_of(&self.this, *n as u128);
+                assert!(
+                    escrow >= minted,
+                    "oep8 escrow {} < erc1155 minted {}",
+                    escrow,
+                    minted
+                );
+            }
+        }
+    }
+}
+
+fn run_escrow(ops: &[Op]) {
+    let mut env = setup_escrow();
+    for op in ops.iter() {
+        env.apply(op);
+        env.check_invariants();
+    }
+}
+
+#[test]
+fn test_escrow_invariants() {
+    for seed in 1..=64 {
+        let ops = random_ops(seed);
+        let res = catch_unwind(AssertUnwindSafe(|| run_escrow(&ops)));
+        assert!(res.is_ok(), "seed {} broke the escrow with {:?}", seed, ops);
+    }
+}
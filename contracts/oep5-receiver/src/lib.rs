@@ -13,11 +13,18 @@ extern crate common;
 
 mod events;
 mod receive;
+#[cfg(all(test, feature = "mock"))]
+mod test;
 
-#[no_mangle]
+//unexported in mock builds like the bridge's, the bridge tests run the receiver through dispatch
+#[cfg_attr(not(feature = "mock"), no_mangle)]
 pub fn invoke() {
     let input = input();
-    let mut source = Source::new(&input);
+    ret(&dispatch(&input))
+}
+
+pub fn dispatch(input: &[u8]) -> Vec<u8> {
+    let mut source = Source::new(input);
     let action = source.read().unwrap();
     let mut sink = Sink::new(12);
     match action {
@@ -32,6 +39,9 @@ pub fn invoke() {
             let bridge = source.read().unwrap();
             sink.write(set_nft_bridge(bridge));
         }
+        "getInstanceId" => {
+            sink.write(get_instance_id());
+        }
         "getAdmin" => {
             sink.write(get_admin());
         }
@@ -72,5 +82,5 @@ pub fn invoke() {
         }
         _ => panic!("unsupported action2!"),
     }
-    ret(sink.bytes())
+    sink.bytes().to_vec()
 }
@@ -1,6 +1,6 @@
 use crate::events::{new_admin_event, new_pending_admin_event};
 use alloc::collections::BTreeMap;
-use common::oep5and8::{owner_of, transfer_oep5};
+use common::oep5and8::{get_contract_vm_type, owner_of, put_contract_vm_type, transfer_oep5};
 use ostd::abi::Sink;
 use ostd::abi::{Decoder, Encoder};
 use ostd::database::{delete, get, put};
@@ -13,6 +13,13 @@ const PREFIX_OEP5_IDS: &[u8] = b"3";
 const KEY_OEP5_CONTRACTS: &[u8] = b"4";
 const KEY_NFT_BRIDGE: &[u8] = b"5";
 
+//set by RECEIVER_INSTANCE_ID at build time, every receiver deployed from this source
+//needs a different value to get a different code hash
+const INSTANCE_ID: &str = match option_env!("RECEIVER_INSTANCE_ID") {
+    Some(id) => id,
+    None => "0",
+};
+
 pub fn initialize(admin: &Address) -> bool {
     assert!(get_admin().is_zero(), "has inited");
     assert!(check_witness(admin), "check admin signature failed");
@@ -29,6 +36,10 @@ pub fn get_nft_bridge() -> Address {
     get(KEY_NFT_BRIDGE).unwrap_or_default()
 }
 
+pub fn get_instance_id() -> &'static str {
+    INSTANCE_ID
+}
+
 pub fn get_admin() -> Address {
     get(KEY_ADMIN).unwrap_or_default()
 }
@@ -80,10 +91,14 @@ pub fn migrate(
     let new_addr = contract_migrate(code, vm_type, name, version, author, email, desc);
     assert!(!new_addr.is_zero(), "migrate failed");
     for (contract, (oep5_ids, oep5_is_neovm)) in oep5_id_map.iter() {
+        //tokens locked before the vm type was recorded only have it in the contract list
+        if get_contract_vm_type(contract).is_none() {
+            put_contract_vm_type(contract, *oep5_is_neovm);
+        }
         oep5_ids.iter().for_each(|&id| {
-            let owner = owner_of(&contract, id, *oep5_is_neovm);
+            let owner = owner_of(&contract, id);
             if owner == address() {
-                transfer_oep5(&contract, &new_addr, id, *oep5_is_neovm);
+                transfer_oep5(&contract, &new_addr, id);
             }
         });
     }
@@ -102,11 +117,12 @@ pub fn withdraw_oep5(
         check_witness(&bridge) || check_witness(&admin),
         "only admin or bridge"
     );
+    record_vm_type(contract, oep5_is_neovm);
     let this = address();
-    let owner = owner_of(contract, token_id, oep5_is_neovm);
+    let owner = owner_of(contract, token_id);
     assert_eq!(owner, this, "invalid owner");
-    transfer_oep5(contract, to, token_id, oep5_is_neovm);
-    let owner = owner_of(contract, token_id, oep5_is_neovm);
+    transfer_oep5(contract, to, token_id);
+    let owner = owner_of(contract, token_id);
     assert_ne!(owner, this, "transfer oep5 failed");
     del_oep5_id(token_id, contract);
     true
@@ -119,13 +135,21 @@ pub fn lock_oep5(contract: &Address, token_id: U128, oep5_is_neovm: bool) -> boo
         check_witness(&bridge) || check_witness(&admin),
         "only admin or bridge"
     );
+    record_vm_type(contract, oep5_is_neovm);
     let this = address();
-    transfer_oep5(contract, &this, token_id, oep5_is_neovm);
+    transfer_oep5(contract, &this, token_id);
     push_oep5_id(token_id, contract);
     push_oep5_contract(contract, oep5_is_neovm);
     true
 }
 
+//the bridge sends the vm type it recorded with every call, the oep5 calls read it from here
+fn record_vm_type(contract: &Address, oep5_is_neovm: bool) {
+    if get_contract_vm_type(contract) != Some(oep5_is_neovm) {
+        put_contract_vm_type(contract, oep5_is_neovm);
+    }
+}
+
 fn push_oep5_id(id: U128, oep5: &Address) {
     let mut ids: Vec<U128> = get_oep5_ids(oep5);
     if !ids.contains(&id) {
@@ -0,0 +1,86 @@
+use crate::receive::*;
+use common::mock::*;
+use ostd::prelude::*;
+
+fn admin() -> Address {
+    Address::repeat_byte(1)
+}
+
+fn user() -> Address {
+    Address::repeat_byte(2)
+}
+
+fn bridge() -> Address {
+    Address::repeat_byte(10)
+}
+
+fn receiver() -> Address {
+    Address::repeat_byte(11)
+}
+
+fn oep5() -> Address {
+    Address::repeat_byte(20)
+}
+
+#[test]
+fn test_admin_handover() {
+    let world = World::new();
+    let handle = world.install();
+    handle.address(&receiver()).witness(&[admin()]);
+    assert!(initialize(&admin()));
+    let new_admin = Address::repeat_byte(4);
+    assert!(set_pending_admin(&new_admin));
+    handle.witness(&[new_admin]);
+    assert!(accept_admin());
+    assert_eq!(get_admin(), new_admin);
+}
+
+#[test]
+fn test_lock_withdraw_and_migrate() {
+    let world = World::new();
+    let fake_oep5 = world.deploy(&oep5(), FakeOep5::default());
+    fake_oep5.borrow_mut().mint(&user(), 1);
+    fake_oep5.borrow_mut().mint(&user(), 2);
+    let handle = world.install();
+    handle.address(&receiver()).witness(&[admin()]);
+    assert!(initialize(&admin()));
+    set_nft_bridge(&bridge());
+
+    handle.witness(&[bridge(), user()]);
+    assert!(lock_oep5(&oep5(), U128::new(1), false));
+    assert!(lock_oep5(&oep5(), U128::new(2), false));
+    assert_eq!(fake_oep5.borrow().owner_of(1), receiver());
+    assert_eq!(get_oep5_ids(&oep5()), vec![U128::new(1), U128::new(2)]);
+
+    handle.witness(&[bridge()]);
+    assert!(withdraw_oep5(&oep5(), &user(), U128::new(1), false));
+    assert_eq!(fake_oep5.borrow().owner_of(1), user());
+    assert_eq!(get_oep5_ids(&oep5()), vec![U128::new(2)]);
+
+    handle.witness(&[admin()]);
+    assert!(migrate(
+        b"code",
+        3,
+        "receiver",
+        "2",
+        "ont",
+        "ont@ont.io",
+        "receiver"
+    ));
+    let new_receiver = migrated_address(b"code");
+    assert_eq!(fake_oep5.borrow().owner_of(2), new_receiver);
+    assert_eq!(fake_oep5.borrow().owner_of(1), user());
+}
+
+#[test]
+#[should_panic(expected = "only admin or bridge")]
+fn test_lock_needs_bridge() {
+    let world = World::new();
+    world.deploy(&oep5(), FakeOep5::default());
+    let handle = world.install();
+    handle.address(&receiver()).witness(&[admin()]);
+    assert!(initialize(&admin()));
+    set_nft_bridge(&bridge());
+    handle.witness(&[user()]);
+    lock_oep5(&oep5(), U128::new(1), false);
+}
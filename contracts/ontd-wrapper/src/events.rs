@@ -14,4 +14,12 @@ pub fn new_admin_event(old_admin: &Address, new_pending_admin: &Address) {
         .address(old_admin)
         .address(new_pending_admin)
         .notify();
-}
\ No newline at end of file
+}
+
+pub fn pause_event() {
+    EventBuilder::new().string("pause").notify();
+}
+
+pub fn unpause_event() {
+    EventBuilder::new().string("unpause").notify();
+}
@@ -13,6 +13,8 @@ extern crate common;
 
 mod events;
 mod tool;
+#[cfg(all(test, feature = "mock"))]
+mod test;
 
 #[no_mangle]
 pub fn invoke() {
@@ -72,10 +74,23 @@ pub fn invoke() {
         b"getOntd" => {
             sink.write(get_ontd());
         }
+        b"pause" => {
+            sink.write(pause());
+        }
+        b"unpause" => {
+            sink.write(unpause());
+        }
+        b"isPaused" => {
+            sink.write(is_paused());
+        }
         b"oep4ToOrc20" => {
             let (ont_acct, eth_acct, amount) = source.read().unwrap();
             sink.write(ontd_to_wont(ont_acct, eth_acct, amount));
         }
+        b"orc20ToOep4" => {
+            let (eth_acct, ont_acct, amount) = source.read().unwrap();
+            sink.write(wont_to_ontd(eth_acct, ont_acct, amount));
+        }
         _ => panic!("unsupported action2!"),
     }
     ret(sink.bytes())
@@ -0,0 +1,237 @@
+use crate::tool::*;
+use common::mock::*;
+use common::oep5and8::ONT_CONTRACT_ADDRESS;
+use core::cell::RefCell;
+use ostd::abi::{Encoder, Source};
+use ostd::mock::RuntimeHandle;
+use ostd::prelude::*;
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+
+fn admin() -> Address {
+    Address::repeat_byte(1)
+}
+
+fn user() -> Address {
+    Address::repeat_byte(2)
+}
+
+fn eth_user() -> Address {
+    Address::repeat_byte(3)
+}
+
+fn bridge() -> Address {
+    Address::repeat_byte(10)
+}
+
+fn wrapper() -> Address {
+    Address::repeat_byte(12)
+}
+
+fn ontd() -> Address {
+    Address::repeat_byte(20)
+}
+
+fn erc20() -> Address {
+    Address::repeat_byte(24)
+}
+
+struct Env {
+    handle: Rc<RuntimeHandle>,
+    ont: Rc<RefCell<FakeOnt>>,
+    ontd: Rc<RefCell<FakeOntd>>,
+    erc20: Rc<RefCell<FakeErc20>>,
+}
+
+//runs a bridge action through the world, the way the wrapper reaches the bridge
+fn call_bridge<T: Encoder>(world: &World, param: T) {
+    let res = world
+        .call_wasm(&bridge(), param)
+        .expect("bridge call failed");
+    assert!(Source::new(&res).read::<bool>().unwrap());
+}
+
+fn panic_message(err: Box<dyn Any + Send>) -> String {
+    match err.downcast::<String>() {
+        Ok(msg) => *msg,
+        Err(err) => err.downcast_ref::<&str>().unwrap_or(&"").to_string(),
+    }
+}
+
+//wrapper wired to ontd, the real bridge and wont, user holds 1000 ontd backed by ont
+fn setup() -> Env {
+    let world = World::new();
+    let fake_ont = world.deploy(&ONT_CONTRACT_ADDRESS, FakeOnt::default());
+    let fake_ontd = world.deploy(&ontd(), FakeOntd::new(&fake_ont, &ontd()));
+    let fake_erc20 = world.deploy(&erc20(), FakeErc20::default());
+    world.deploy_wasm(&bridge(), bridge::dispatch);
+    fake_ont.borrow_mut().mint(&ontd(), 1000);
+    fake_ontd.borrow_mut().balances.insert(user(), 1000);
+    let handle = world.install();
+    handle.address(&wrapper()).witness(&[admin()]);
+    call_bridge(&world, ("init", &admin()));
+    call_bridge(
+        &world,
+        (
+            "registerOep4Erc20Pair",
+            "ont",
+            &ONT_CONTRACT_ADDRESS,
+            &erc20(),
+            false,
+        ),
+    );
+    assert!(initialize(&admin()));
+    set_ontd(&ontd());
+    set_bridge(&bridge());
+    set_token_pair_name(b"ont");
+    Env {
+        handle,
+        ont: fake_ont,
+        ontd: fake_ontd,
+        erc20: fake_erc20,
+    }
+}
+
+#[test]
+fn test_admin_handover() {
+    let world = World::new();
+    let handle = world.install();
+    handle.address(&wrapper()).witness(&[admin()]);
+    assert!(initialize(&admin()));
+    let new_admin = Address::repeat_byte(4);
+    assert!(set_pending_admin(&new_admin));
+    handle.witness(&[new_admin]);
+    assert!(accept_admin());
+    assert_eq!(get_admin(), new_admin);
+    assert_eq!(get_pending_admin(), Address::default());
+}
+
+#[test]
+fn test_settings() {
+    let world = World::new();
+    let handle = world.install();
+    handle.address(&wrapper()).witness(&[admin()]);
+    assert!(initialize(&admin()));
+    set_ontd(&Address::repeat_byte(20));
+    set_bridge(&Address::repeat_byte(10));
+    set_token_pair_name(b"ont");
+    assert_eq!(get_ontd(), Address::repeat_byte(20));
+    assert_eq!(get_bridge(), Address::repeat_byte(10));
+    assert_eq!(get_token_pair_name(), b"ont".to_vec());
+    assert!(pause());
+    assert!(is_paused());
+    assert!(unpause());
+    assert!(!is_paused());
+}
+
+#[test]
+#[should_panic(expected = "wrapper is paused")]
+fn test_paused_wrapper_rejects_swap() {
+    let world = World::new();
+    let handle = world.install();
+    handle.address(&wrapper()).witness(&[admin()]);
+    assert!(initialize(&admin()));
+    assert!(pause());
+    handle.witness(&[user()]);
+    ontd_to_wont(&user(), &Address::repeat_byte(3), U128::new(1));
+}
+
+#[test]
+fn test_migrate() {
+    let env = setup();
+    assert!(migrate(
+        b"code",
+        3,
+        "wrapper",
+        "2",
+        "ont",
+        "ont@ont.io",
+        "ontd wrapper",
+    ));
+    let new_wrapper = migrated_address(b"code");
+    assert!(!new_wrapper.is_zero());
+    assert_ne!(new_wrapper, wrapper());
+    //the wrapper never keeps funds between calls, so nothing is left behind
+    assert_eq!(env.ont.borrow().balance_of(&wrapper()), 0);
+    assert_eq!(env.ontd.borrow().balance_of(&wrapper()), 0);
+}
+
+#[test]
+#[should_panic(expected = "check admin signature failed")]
+fn test_migrate_needs_admin() {
+    let env = setup();
+    env.handle.witness(&[user()]);
+    migrate(
+        b"code",
+        3,
+        "wrapper",
+        "2",
+        "ont",
+        "ont@ont.io",
+        "ontd wrapper",
+    );
+}
+
+#[test]
+fn test_ontd_wont_round_trip() {
+    let env = setup();
+    env.handle.witness(&[user()]);
+    assert!(ontd_to_wont(&user(), &eth_user(), U128::new(300)));
+    assert_eq!(env.ontd.borrow().balance_of(&user()), 700);
+    assert_eq!(env.ont.borrow().balance_of(&user()), 0);
+    assert_eq!(env.ont.borrow().balance_of(&bridge()), 300);
+    assert_eq!(env.erc20.borrow().balance_of(&eth_user()), 300);
+
+    env.handle.witness(&[eth_user()]);
+    assert!(wont_to_ontd(&eth_user(), &user(), U128::new(100)));
+    assert_eq!(env.erc20.borrow().balance_of(&eth_user()), 200);
+    assert_eq!(env.ont.borrow().balance_of(&bridge()), 200);
+    assert_eq!(env.ont.borrow().balance_of(&wrapper()), 0);
+    assert_eq!(env.ontd.borrow().balance_of(&wrapper()), 0);
+    assert_eq!(env.ontd.borrow().balance_of(&user()), 800);
+    //every ontd and wont is still backed by ont
+    assert_eq!(env.ont.borrow().balance_of(&ontd()), 800);
+    assert_eq!(env.ontd.borrow().total_supply(), 800);
+    assert_eq!(env.erc20.borrow().total_supply(), 200);
+}
+
+#[test]
+fn test_ontd_to_wont_without_ont_received() {
+    let env = setup();
+    env.ontd.borrow_mut().silent_ontd2ont = true;
+    env.handle.witness(&[user()]);
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        ontd_to_wont(&user(), &eth_user(), U128::new(300))
+    }));
+    assert!(panic_message(res.unwrap_err()).contains("no ont received from ontd"));
+    //the burned ontd only comes back through the revert of the aborted transaction
+    assert_eq!(env.ontd.borrow().balance_of(&user()), 700);
+    assert_eq!(env.ont.borrow().balance_of(&bridge()), 0);
+    assert_eq!(env.erc20.borrow().total_supply(), 0);
+}
+
+#[test]
+fn test_ontd_to_wont_mint_failure() {
+    for failure in [EvmFailure::MintNoop, EvmFailure::MintRevert] {
+        let env = setup();
+        env.erc20.borrow_mut().failure = failure;
+        env.handle.witness(&[user()]);
+        let res = catch_unwind(AssertUnwindSafe(|| {
+            ontd_to_wont(&user(), &eth_user(), U128::new(300))
+        }));
+        let expected = match failure {
+            EvmFailure::MintRevert => "mint failed, reverted: mint reverted",
+            _ => "mint failed",
+        };
+        assert!(panic_message(res.unwrap_err()).contains(expected));
+    }
+}
+
+#[test]
+#[should_panic(expected = "insufficient erc20 balance")]
+fn test_wont_to_ontd_without_wont() {
+    let env = setup();
+    env.handle.witness(&[eth_user()]);
+    wont_to_ontd(&eth_user(), &user(), U128::new(100));
+}
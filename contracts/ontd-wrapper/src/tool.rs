@@ -1,15 +1,18 @@
-use crate::events::{new_admin_event, new_pending_admin_event};
-use common::oep5and8::{ontd_to_ont, call_wasm_contract};
+use crate::events::{new_admin_event, new_pending_admin_event, pause_event, unpause_event};
+use common::oep5and8::{
+    balance_of_oep4, call_wasm_contract, ont_to_ontd, ontd_to_ont, transfer_oep4,
+};
 use ostd::contract::ont;
 use ostd::database::{delete, get, put};
 use ostd::prelude::*;
-use ostd::runtime::{check_witness, contract_migrate};
+use ostd::runtime::{address, check_witness, contract_migrate};
 
 const KEY_ADMIN: &[u8] = b"1";
 const KEY_PENDING_ADMIN: &[u8] = b"2";
 const KEY_ONTD: &[u8] = b"3";
 const KEY_BRIDGE: &[u8] = b"4";
 const KEY_TOKEN_PAIR_NAME: &[u8] = b"5";
+const KEY_PAUSED: &[u8] = b"6";
 
 pub fn initialize(admin: &Address) -> bool {
     assert!(get_admin().is_zero(), "has inited");
@@ -46,6 +49,24 @@ pub fn get_bridge() -> Address {
     get(KEY_BRIDGE).unwrap_or_default()
 }
 
+pub fn pause() -> bool {
+    check_admin();
+    put(KEY_PAUSED, true);
+    pause_event();
+    true
+}
+
+pub fn unpause() -> bool {
+    check_admin();
+    delete(KEY_PAUSED);
+    unpause_event();
+    true
+}
+
+pub fn is_paused() -> bool {
+    get(KEY_PAUSED).unwrap_or_default()
+}
+
 pub fn get_admin() -> Address {
     get(KEY_ADMIN).unwrap_or_default()
 }
@@ -92,15 +113,15 @@ pub fn migrate(
 
 pub fn ontd_to_wont(from: &Address, eth_acct: &Address, amount: U128) -> bool {
     check_sig(from);
+    assert!(!is_paused(), "wrapper is paused");
     let ontd = &get_ontd();
     //第一步  ontd -> ONT
     let from_bal_before = ont::v2::balance_of(from);
     ontd_to_ont(ontd, from, amount);
     let from_bal_after = ont::v2::balance_of(from);
     let delta = from_bal_after - from_bal_before;
-    if delta.is_zero() {
-        return false;
-    }
+    //ontd is burned at this point, abort so the transaction gives it back
+    assert!(!delta.is_zero(), "no ont received from ontd");
     //第二步 ONT 打给该合约地址
     let bridge = &get_bridge();
     assert!(!bridge.is_zero(), "bridge is zero");
@@ -109,6 +130,34 @@ pub fn ontd_to_wont(from: &Address, eth_acct: &Address, amount: U128) -> bool {
     true
 }
 
+pub fn wont_to_ontd(eth_acct: &Address, to: &Address, amount: U128) -> bool {
+    check_sig(eth_acct);
+    assert!(!is_paused(), "wrapper is paused");
+    let this = &address();
+    let bridge = &get_bridge();
+    assert!(!bridge.is_zero(), "bridge is zero");
+    //第一步 wONT -> ONT, ONT 打给该合约地址
+    let ont_bal_before = ont::v2::balance_of(this);
+    let res: bool = call_wasm_contract(
+        bridge,
+        ("orc20ToOep4", eth_acct, this, amount, get_token_pair_name()),
+    );
+    assert!(res, "orc20ToOep4 failed");
+    let ont_bal_after = ont::v2::balance_of(this);
+    let delta = ont_bal_after - ont_bal_before;
+    assert!(!delta.is_zero(), "receive ont failed");
+    //第二步 ONT -> ontd
+    let ontd = &get_ontd();
+    let ontd_bal_before = balance_of_oep4(ontd, this);
+    ont_to_ontd(ontd, this, delta);
+    let ontd_bal_after = balance_of_oep4(ontd, this);
+    let ontd_delta = ontd_bal_after - ontd_bal_before;
+    assert!(!ontd_delta.is_zero(), "ont2ontd failed");
+    //第三步 ontd 打给用户
+    transfer_oep4(ontd, this, to, ontd_delta);
+    true
+}
+
 fn check_admin() {
     assert!(check_witness(&get_admin()), "check admin signature failed");
 }